@@ -0,0 +1,11 @@
+//! Stand-in for the private Anathema-Breaker core.
+//!
+//! `slime-core`'s `real_ab` feature names `anathema_breaker_core`, and the
+//! workspace patches it to this crate so every other build resolves from a
+//! checkout alone. A `real_ab` build replaces it with a local checkout of the
+//! engine (see the bundle README); built as is, it stops here.
+
+compile_error!(
+    "real_ab needs the Anathema-Breaker core: build with \
+     --config 'patch.crates-io.anathema_breaker_core.path=\"<path to Anathema-Breaker>\"'"
+);
@@ -0,0 +1,88 @@
+//! Egress wire ABI (CANON v0, `specs/EGRESS_SOCKET_SPEC.md`).
+//!
+//! What the runner and an actuator bridge must agree on, and nothing else:
+//! the fixed socket path and the frame. One AUTHORIZED part becomes one
+//! fixed 32-byte little-endian frame:
+//!
+//! ```text
+//! 0..8    domain_id        u64
+//! 8..16   magnitude        u64
+//! 16..32  actuation_token  u128
+//! ```
+//!
+//! No framing, no header, no version byte: a reader consumes exactly
+//! `FRAME_BYTES` per effect.
+
+#![cfg_attr(not(test), no_std)]
+
+/// Created and owned by the actuator bridge; SLIME connects as client.
+pub const EGRESS_SOCKET_PATH: &str = "/run/slime/egress.sock";
+
+pub const FRAME_BYTES: usize = 32;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct AuthorizedEffect {
+    pub domain_id: u64,
+    pub magnitude: u64,
+    pub actuation_token: u128,
+}
+
+impl AuthorizedEffect {
+    pub const EMPTY: Self = Self {
+        domain_id: 0,
+        magnitude: 0,
+        actuation_token: 0,
+    };
+
+    pub fn encode(&self) -> [u8; FRAME_BYTES] {
+        let mut frame = [0u8; FRAME_BYTES];
+        frame[0..8].copy_from_slice(&self.domain_id.to_le_bytes());
+        frame[8..16].copy_from_slice(&self.magnitude.to_le_bytes());
+        frame[16..32].copy_from_slice(&self.actuation_token.to_le_bytes());
+        frame
+    }
+
+    /// Every 32-byte string is a frame; the actuator decides what it means.
+    pub fn decode(frame: &[u8; FRAME_BYTES]) -> Self {
+        let mut domain_id = [0u8; 8];
+        let mut magnitude = [0u8; 8];
+        let mut actuation_token = [0u8; 16];
+        domain_id.copy_from_slice(&frame[0..8]);
+        magnitude.copy_from_slice(&frame[8..16]);
+        actuation_token.copy_from_slice(&frame[16..32]);
+        Self {
+            domain_id: u64::from_le_bytes(domain_id),
+            magnitude: u64::from_le_bytes(magnitude),
+            actuation_token: u128::from_le_bytes(actuation_token),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_is_32_bytes_little_endian() {
+        let effect = AuthorizedEffect {
+            domain_id: 0x0102,
+            magnitude: 0x0A0B_0C0D,
+            actuation_token: 1 << 120,
+        };
+        let frame = effect.encode();
+        assert_eq!(&frame[0..8], &[0x02, 0x01, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(&frame[8..16], &[0x0D, 0x0C, 0x0B, 0x0A, 0, 0, 0, 0]);
+        assert_eq!(&frame[16..31], &[0u8; 15]);
+        assert_eq!(frame[31], 1);
+    }
+
+    #[test]
+    fn decode_inverts_encode() {
+        let effect = AuthorizedEffect {
+            domain_id: u64::MAX,
+            magnitude: 7,
+            actuation_token: u128::MAX - 1,
+        };
+        assert_eq!(AuthorizedEffect::decode(&effect.encode()), effect);
+    }
+}
@@ -0,0 +1,133 @@
+//! Reference actuator bridge (EGRESS_SOCKET_SPEC, "Environment
+//! Responsibilities").
+//!
+//! Owns `/run/slime/egress.sock`: binds it `0660`, accepts the runner and
+//! prints one line per frame on stdout. It actuates nothing — a real
+//! bridge replaces `actuate`. Like the runner, it takes no configuration;
+//! ownership of the socket (`actuator:slime-actuator`) is the unit's job.
+//!
+//! A connection that ends mid-frame is invalid (partial reads are not
+//! frames): the fragment is dropped, reported on stderr, and the bridge
+//! goes back to accepting. The runner reconnects on its own.
+
+use slime_abi::{AuthorizedEffect, EGRESS_SOCKET_PATH, FRAME_BYTES};
+use std::io::{self, ErrorKind, Read, Write};
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::net::UnixListener;
+use std::process;
+
+fn main() {
+    // A stale socket from a previous run would make bind fail.
+    let _ = std::fs::remove_file(EGRESS_SOCKET_PATH);
+    let listener = UnixListener::bind(EGRESS_SOCKET_PATH).unwrap_or_else(|e| {
+        eprintln!("cannot bind {}: {}", EGRESS_SOCKET_PATH, e);
+        process::exit(1);
+    });
+    let mode = std::fs::Permissions::from_mode(0o660);
+    if let Err(e) = std::fs::set_permissions(EGRESS_SOCKET_PATH, mode) {
+        eprintln!("cannot chmod {}: {}", EGRESS_SOCKET_PATH, e);
+        process::exit(1);
+    }
+
+    for conn in listener.incoming() {
+        let result = conn.and_then(|stream| drain(stream, io::stdout().lock()));
+        if let Err(e) = result {
+            eprintln!("egress: {}", e);
+        }
+    }
+}
+
+/// Actuate every frame on `r` until EOF. Returns the frame count.
+fn drain(mut r: impl Read, mut out: impl Write) -> io::Result<u64> {
+    let mut frame = [0u8; FRAME_BYTES];
+    let mut frames = 0;
+    while read_frame(&mut r, &mut frame)? {
+        actuate(&AuthorizedEffect::decode(&frame), &mut out)?;
+        frames += 1;
+    }
+    Ok(frames)
+}
+
+/// Fill `frame`. `false` on EOF at a frame boundary, an error on EOF
+/// inside one.
+fn read_frame(r: &mut impl Read, frame: &mut [u8; FRAME_BYTES]) -> io::Result<bool> {
+    let mut filled = 0;
+    while filled < FRAME_BYTES {
+        match r.read(&mut frame[filled..]) {
+            Ok(0) if filled == 0 => return Ok(false),
+            Ok(0) => {
+                return Err(io::Error::new(
+                    ErrorKind::UnexpectedEof,
+                    format!("partial frame ({} of {} bytes)", filled, FRAME_BYTES),
+                ))
+            }
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(true)
+}
+
+fn actuate(effect: &AuthorizedEffect, out: &mut impl Write) -> io::Result<()> {
+    writeln!(
+        out,
+        "domain_id={} magnitude={} actuation_token={}",
+        effect.domain_id, effect.magnitude, effect.actuation_token
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn effect(domain_id: u64, magnitude: u64) -> AuthorizedEffect {
+        AuthorizedEffect {
+            domain_id,
+            magnitude,
+            actuation_token: 0,
+        }
+    }
+
+    #[test]
+    fn drain_actuates_each_frame_in_order() {
+        let mut wire = Vec::new();
+        wire.extend_from_slice(&effect(1, 10).encode());
+        wire.extend_from_slice(&effect(2, 3).encode());
+        let mut out = Vec::new();
+        assert_eq!(drain(&wire[..], &mut out).unwrap(), 2);
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "domain_id=1 magnitude=10 actuation_token=0\n\
+             domain_id=2 magnitude=3 actuation_token=0\n"
+        );
+    }
+
+    #[test]
+    fn partial_trailing_frame_is_an_error() {
+        let mut wire = effect(1, 10).encode().to_vec();
+        wire.extend_from_slice(&[0u8; FRAME_BYTES - 1]);
+        let mut out = Vec::new();
+        let err = drain(&wire[..], &mut out).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+        // The whole frame before it was still actuated.
+        assert_eq!(out.iter().filter(|&&b| b == b'\n').count(), 1);
+    }
+
+    #[test]
+    fn frames_split_across_reads_are_reassembled() {
+        // Two bytes per read, as a slow socket might deliver them.
+        struct Trickle<'a>(&'a [u8]);
+        impl Read for Trickle<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                let n = self.0.len().min(buf.len()).min(2);
+                buf[..n].copy_from_slice(&self.0[..n]);
+                self.0 = &self.0[n..];
+                Ok(n)
+            }
+        }
+        let wire = effect(7, 1).encode();
+        let mut out = Vec::new();
+        assert_eq!(drain(Trickle(&wire), &mut out).unwrap(), 1);
+    }
+}
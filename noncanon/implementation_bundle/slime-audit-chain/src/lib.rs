@@ -0,0 +1,356 @@
+//! Hash chain for the operator audit log.
+//!
+//! Every line carries the BLAKE3 hash of the previous line (`prev`) and a
+//! sequence number (`seq`). Removing, reordering or editing a line breaks
+//! the link to the line after it. Every `CHECKPOINT_INTERVAL + 1`-th line is
+//! a checkpoint record, so a gap between checkpoints is also visible.
+//!
+//! ```text
+//! {"seq":0,"kind":"boot","ts_ms":..,"prev":"0000…"}
+//! {"seq":1,"kind":"decision","ts_ms":..,"stage":..,…,"prev":"<blake3 of line 0>"}
+//! ```
+//!
+//! The hash covers the exact line bytes without the trailing `\n`.
+//! The runner writes through [`Chain`]; `slime-audit verify` walks a file
+//! with [`verify`].
+//!
+//! The chain is unkeyed, so a file alone cannot show that its tail was
+//! cut off cleanly. The runner therefore reports each checkpoint as an
+//! [`Anchor`] (`seq` and line hash) on its stderr event log, outside the
+//! file; [`verify_anchored`] holds a log to one such anchor. Records after
+//! the last anchor are covered by the file alone.
+
+/// `prev` of the first line of a log.
+pub const GENESIS: [u8; 32] = [0u8; 32];
+
+/// Non-checkpoint lines between two checkpoints.
+pub const CHECKPOINT_INTERVAL: u64 = 64;
+
+/// Upper bound on one sealed line, newline included.
+pub const MAX_LINE_BYTES: usize = 512;
+
+const SEQ_PREFIX: &[u8] = b"{\"seq\":";
+const PREV_KEY: &[u8] = b",\"prev\":\"";
+const CHECKPOINT_KIND: &[u8] = b"\"kind\":\"checkpoint\"";
+
+/// Writer-side chain state: next sequence number and hash of the last line.
+/// `Copy`, so a writer can seal into a copy and keep it only once the line
+/// is on disk.
+#[derive(Clone, Copy)]
+pub struct Chain {
+    seq: u64,
+    prev: [u8; 32],
+}
+
+impl Chain {
+    pub fn genesis() -> Self {
+        Self {
+            seq: 0,
+            prev: GENESIS,
+        }
+    }
+
+    /// Continue after `last_line` (without its newline), the final line of
+    /// an existing log. `None` if it is not a sealed line.
+    pub fn resume(last_line: &[u8]) -> Option<Self> {
+        let (seq, _) = parse_line(last_line)?;
+        Some(Self {
+            seq: seq.checked_add(1)?,
+            prev: hash(last_line),
+        })
+    }
+
+    /// The last sealed line, if any.
+    pub fn last(&self) -> Option<Anchor> {
+        Some(Anchor {
+            seq: self.seq.checked_sub(1)?,
+            hash: self.prev,
+        })
+    }
+
+    /// True if the next line must be a checkpoint.
+    pub fn checkpoint_due(&self) -> bool {
+        is_checkpoint_seq(self.seq)
+    }
+
+    /// Seal `fields` (comma-separated JSON members, no braces) into one
+    /// line in `out` and advance the chain. Returns the line length.
+    pub fn seal(&mut self, fields: &[u8], out: &mut [u8]) -> Option<usize> {
+        use std::io::Write;
+
+        let next = self.seq.checked_add(1)?;
+        let capacity = out.len();
+        let mut w = &mut out[..];
+        write!(w, "{{\"seq\":{},", self.seq).ok()?;
+        w.write_all(fields).ok()?;
+        w.write_all(PREV_KEY).ok()?;
+        w.write_all(&to_hex(&self.prev)).ok()?;
+        w.write_all(b"\"}").ok()?;
+        let line_len = capacity - w.len();
+        w.write_all(b"\n").ok()?;
+
+        self.prev = hash(&out[..line_len]);
+        self.seq = next;
+        Some(line_len + 1)
+    }
+}
+
+/// Why verification stopped.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Break {
+    /// Line is not a sealed record (bad prefix, missing `prev`, bad hex).
+    Malformed,
+    /// Last line has no terminating newline.
+    Truncated,
+    /// `seq` is not the previous line's `seq + 1` (or 0 on the first line).
+    Sequence,
+    /// `prev` does not match the hash of the previous line.
+    Link,
+    /// Checkpoint missing where required, or present where not.
+    Checkpoint,
+    /// The anchored line is missing, or does not hash to the anchor.
+    Anchor,
+}
+
+impl Break {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Break::Malformed => "malformed record",
+            Break::Truncated => "truncated record",
+            Break::Sequence => "sequence gap",
+            Break::Link => "previous-hash mismatch",
+            Break::Checkpoint => "checkpoint misplaced",
+            Break::Anchor => "anchor mismatch",
+        }
+    }
+}
+
+/// An intact log.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Summary {
+    pub records: u64,
+    pub checkpoints: u64,
+    /// Hash of the last line (or `GENESIS` for an empty log).
+    pub head: [u8; 32],
+}
+
+/// A line's `seq` and hash, kept outside the log. Written and parsed as
+/// `<seq>:<64 hex>`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Anchor {
+    pub seq: u64,
+    pub hash: [u8; 32],
+}
+
+impl Anchor {
+    pub fn parse(s: &str) -> Option<Self> {
+        let (seq, hex) = s.split_once(':')?;
+        if hex.len() != 64 {
+            return None;
+        }
+        Some(Self {
+            seq: seq.parse().ok()?,
+            hash: from_hex(hex.as_bytes())?,
+        })
+    }
+}
+
+impl std::fmt::Display for Anchor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let hex = to_hex(&self.hash);
+        write!(f, "{}:{}", self.seq, std::str::from_utf8(&hex).unwrap_or(""))
+    }
+}
+
+/// Walk a whole log. On failure returns the 1-based number of the first
+/// line whose link does not hold.
+pub fn verify(log: &[u8]) -> Result<Summary, (u64, Break)> {
+    walk(log, None)
+}
+
+/// `verify`, and the log must contain the anchored line unchanged. A log
+/// cut before the anchor fails at the anchor's line number.
+pub fn verify_anchored(log: &[u8], anchor: &Anchor) -> Result<Summary, (u64, Break)> {
+    walk(log, Some(anchor))
+}
+
+fn walk(log: &[u8], anchor: Option<&Anchor>) -> Result<Summary, (u64, Break)> {
+    let mut summary = Summary {
+        records: 0,
+        checkpoints: 0,
+        head: GENESIS,
+    };
+    let mut rest = log;
+    while !rest.is_empty() {
+        let line_no = summary.records + 1;
+        let Some(nl) = rest.iter().position(|&b| b == b'\n') else {
+            return Err((line_no, Break::Truncated));
+        };
+        let line = &rest[..nl];
+        rest = &rest[nl + 1..];
+
+        let (seq, prev) = parse_line(line).ok_or((line_no, Break::Malformed))?;
+        if seq != summary.records {
+            return Err((line_no, Break::Sequence));
+        }
+        if prev != summary.head {
+            return Err((line_no, Break::Link));
+        }
+        let is_checkpoint = contains(line, CHECKPOINT_KIND);
+        if is_checkpoint != is_checkpoint_seq(seq) {
+            return Err((line_no, Break::Checkpoint));
+        }
+
+        summary.checkpoints += is_checkpoint as u64;
+        summary.records += 1;
+        summary.head = hash(line);
+        if anchor.is_some_and(|a| a.seq == seq && a.hash != summary.head) {
+            return Err((line_no, Break::Anchor));
+        }
+    }
+    if let Some(a) = anchor {
+        if summary.records <= a.seq {
+            return Err((a.seq.saturating_add(1), Break::Anchor));
+        }
+    }
+    Ok(summary)
+}
+
+pub fn hash(line: &[u8]) -> [u8; 32] {
+    *blake3::hash(line).as_bytes()
+}
+
+pub fn to_hex(bytes: &[u8; 32]) -> [u8; 64] {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    let mut out = [0u8; 64];
+    for (i, b) in bytes.iter().enumerate() {
+        out[2 * i] = DIGITS[(b >> 4) as usize];
+        out[2 * i + 1] = DIGITS[(b & 0x0f) as usize];
+    }
+    out
+}
+
+fn is_checkpoint_seq(seq: u64) -> bool {
+    seq % (CHECKPOINT_INTERVAL + 1) == CHECKPOINT_INTERVAL
+}
+
+/// Extract `(seq, prev)` from a sealed line.
+fn parse_line(line: &[u8]) -> Option<(u64, [u8; 32])> {
+    let rest = line.strip_prefix(SEQ_PREFIX)?;
+    let digits = rest.iter().take_while(|b| b.is_ascii_digit()).count();
+    let seq = std::str::from_utf8(&rest[..digits]).ok()?.parse().ok()?;
+
+    // `prev` is always the last member: `,"prev":"<64 hex>"}`.
+    let tail_len = PREV_KEY.len() + 64 + 2;
+    let tail = line.get(line.len().checked_sub(tail_len)?..)?;
+    let hex = tail.strip_prefix(PREV_KEY)?.strip_suffix(b"\"}")?;
+    Some((seq, from_hex(hex)?))
+}
+
+fn from_hex(hex: &[u8]) -> Option<[u8; 32]> {
+    fn nibble(c: u8) -> Option<u8> {
+        match c {
+            b'0'..=b'9' => Some(c - b'0'),
+            b'a'..=b'f' => Some(c - b'a' + 10),
+            _ => None,
+        }
+    }
+    let mut out = [0u8; 32];
+    for (i, pair) in hex.chunks_exact(2).enumerate() {
+        *out.get_mut(i)? = nibble(pair[0])? << 4 | nibble(pair[1])?;
+    }
+    Some(out)
+}
+
+fn contains(hay: &[u8], needle: &[u8]) -> bool {
+    hay.windows(needle.len()).any(|w| w == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build(n: usize) -> Vec<u8> {
+        let mut chain = Chain::genesis();
+        let mut log = Vec::new();
+        let mut buf = [0u8; MAX_LINE_BYTES];
+        for i in 0..n {
+            let fields = if chain.checkpoint_due() {
+                "\"kind\":\"checkpoint\",\"ts_ms\":0".to_string()
+            } else {
+                format!("\"kind\":\"decision\",\"ts_ms\":{}", i)
+            };
+            let len = chain.seal(fields.as_bytes(), &mut buf).unwrap();
+            log.extend_from_slice(&buf[..len]);
+        }
+        log
+    }
+
+    #[test]
+    fn sealed_line_known_shape() {
+        let mut chain = Chain::genesis();
+        let mut buf = [0u8; MAX_LINE_BYTES];
+        let len = chain.seal(b"\"kind\":\"boot\"", &mut buf).unwrap();
+        let line = std::str::from_utf8(&buf[..len]).unwrap();
+        assert_eq!(
+            line,
+            format!("{{\"seq\":0,\"kind\":\"boot\",\"prev\":\"{}\"}}\n", "0".repeat(64))
+        );
+    }
+
+    #[test]
+    fn verify_accepts_intact_chain_with_checkpoints() {
+        let log = build(200);
+        let summary = verify(&log).unwrap();
+        assert_eq!(summary.records, 200);
+        assert_eq!(summary.checkpoints, 3);
+        assert_eq!(verify(b""), Ok(Summary { records: 0, checkpoints: 0, head: GENESIS }));
+    }
+
+    #[test]
+    fn resume_continues_the_same_chain() {
+        let log = build(10);
+        let last = log[..log.len() - 1].rsplit(|&b| b == b'\n').next().unwrap();
+        let mut chain = Chain::resume(last).unwrap();
+        let mut buf = [0u8; MAX_LINE_BYTES];
+        let len = chain.seal(b"\"kind\":\"boot\"", &mut buf).unwrap();
+        let mut resumed = log.clone();
+        resumed.extend_from_slice(&buf[..len]);
+        assert_eq!(verify(&resumed).unwrap().records, 11);
+    }
+
+    #[test]
+    fn last_names_the_sealed_line() {
+        let mut chain = Chain::genesis();
+        assert_eq!(chain.last(), None);
+        let mut buf = [0u8; MAX_LINE_BYTES];
+        let len = chain.seal(b"\"kind\":\"boot\"", &mut buf).unwrap();
+        let anchor = chain.last().unwrap();
+        assert_eq!(anchor, Anchor { seq: 0, hash: hash(&buf[..len - 1]) });
+        assert_eq!(Anchor::parse(&anchor.to_string()), Some(anchor));
+        assert_eq!(Anchor::parse("0:abc"), None);
+    }
+
+    #[test]
+    fn anchor_detects_a_cut_tail() {
+        let log = build(140);
+        let lines: Vec<&[u8]> = log.split_inclusive(|&b| b == b'\n').collect();
+        let checkpoint = lines[129].strip_suffix(b"\n").unwrap();
+        let anchor = Anchor { seq: 129, hash: hash(checkpoint) };
+
+        assert_eq!(verify_anchored(&log, &anchor).unwrap().records, 140);
+        // Whole trailing lines gone: the file alone still verifies.
+        let cut = lines[..120].concat();
+        assert!(verify(&cut).is_ok());
+        assert_eq!(verify_anchored(&cut, &anchor), Err((130, Break::Anchor)));
+        let other = Anchor { hash: hash(b"x"), ..anchor };
+        assert_eq!(verify_anchored(&log, &other), Err((130, Break::Anchor)));
+    }
+
+    #[test]
+    fn hex_round_trip() {
+        let h = hash(b"slime");
+        assert_eq!(from_hex(&to_hex(&h)), Some(h));
+        assert_eq!(from_hex(b"zz"), None);
+    }
+}
@@ -0,0 +1,476 @@
+//! Request parse — CBOR (RFC 8949) body bytes to an `ActionRequest`.
+//!
+//! The JSON field set, binary:
+//!
+//! ```text
+//! {"domain": tstr, "magnitude": magnitude, ? "payload": bstr}
+//! {"parts": [+ {"domain": tstr, "magnitude": magnitude}], ? "payload": bstr}
+//! magnitude = uint / 4([exponent, uint])    ; exponent in -18..=0
+//! ```
+//!
+//! A tag-4 decimal fraction carries what a JSON literal spells: `1.50` is
+//! `4([-2, 150])`. Floats, negatives and positive exponents are format
+//! failures, as signs and exponents are in JSON. The payload is the raw
+//! bytes, hashed like a decoded base64 payload and held to the same limit.
+//!
+//! Definite lengths only, text keys only, no key twice, one top-level item
+//! and nothing after it. The decoder does not recurse: the request shape
+//! is read by straight-line code, and values under unknown keys are
+//! skipped by counting the items still owed, never more than the bytes
+//! left.
+
+use crate::corespec::{MAX_COMPOSITE_PARTS, MAX_FRACTION_DIGITS, MAX_PAYLOAD_BYTES};
+use crate::parse::{ActionPart, ActionRequest, DecimalMagnitude};
+
+// Major types.
+const UINT: u8 = 0;
+const NINT: u8 = 1;
+const BYTES: u8 = 2;
+const TEXT: u8 = 3;
+const ARRAY: u8 = 4;
+const MAP: u8 = 5;
+const TAG: u8 = 6;
+
+const DECIMAL_FRACTION: u64 = 4;
+
+/// Keys read at the top level; each may appear once.
+const KEYS: [&str; 5] = ["domain", "magnitude", "payload", "parts", "signature"];
+
+struct Reader<'a> {
+    rest: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: u64) -> Option<&'a [u8]> {
+        if n > self.rest.len() as u64 {
+            return None;
+        }
+        let (head, rest) = self.rest.split_at(n as usize);
+        self.rest = rest;
+        Some(head)
+    }
+
+    /// Major type and argument of the next item. Indefinite lengths and
+    /// reserved encodings are `None`.
+    fn head(&mut self) -> Option<(u8, u64)> {
+        let initial = self.take(1)?[0];
+        let arg = match initial & 0x1f {
+            n @ 0..=23 => n as u64,
+            24 => self.take(1)?[0] as u64,
+            25 => u16::from_be_bytes(self.take(2)?.try_into().ok()?) as u64,
+            26 => u32::from_be_bytes(self.take(4)?.try_into().ok()?) as u64,
+            27 => u64::from_be_bytes(self.take(8)?.try_into().ok()?),
+            _ => return None,
+        };
+        Some((initial >> 5, arg))
+    }
+
+    fn expect(&mut self, major: u8) -> Option<u64> {
+        let (m, arg) = self.head()?;
+        (m == major).then_some(arg)
+    }
+
+    fn text(&mut self) -> Option<&'a str> {
+        let n = self.expect(TEXT)?;
+        core::str::from_utf8(self.take(n)?).ok()
+    }
+
+    fn bytes(&mut self) -> Option<&'a [u8]> {
+        let n = self.expect(BYTES)?;
+        self.take(n)
+    }
+
+    /// Pass over one well-formed item of any shape; returns its encoding.
+    fn item(&mut self) -> Option<&'a [u8]> {
+        let start = self.rest;
+        let mut owed: u64 = 1;
+        while owed > 0 {
+            owed -= 1;
+            let (major, arg) = self.head()?;
+            match major {
+                BYTES | TEXT => {
+                    self.take(arg)?;
+                }
+                ARRAY => owed = owed.checked_add(arg)?,
+                MAP => owed = owed.checked_add(arg.checked_mul(2)?)?,
+                TAG => owed += 1,
+                // Integers and simple values are all head.
+                _ => {}
+            }
+            // Every item still owed takes at least one byte.
+            if owed > self.rest.len() as u64 {
+                return None;
+            }
+        }
+        Some(&start[..start.len() - self.rest.len()])
+    }
+}
+
+/// Parse a CBOR request body. Same result type, limits and `None` rule as
+/// `parse_request`.
+pub fn parse_request_cbor(body: &[u8]) -> Option<ActionRequest> {
+    let mut r = Reader { rest: body };
+    let mut parts = [ActionPart::EMPTY; MAX_COMPOSITE_PARTS];
+    let mut part_count = 0;
+    let (mut domain, mut magnitude, mut payload) = (None, None, &b""[..]);
+    #[cfg(feature = "corespec_signed_requests")]
+    let mut signature = None;
+
+    let mut seen = [false; KEYS.len()];
+    for _ in 0..r.expect(MAP)? {
+        let key = r.text()?;
+        if let Some(k) = KEYS.iter().position(|&known| known == key) {
+            if seen[k] {
+                return None;
+            }
+            seen[k] = true;
+        }
+        match key {
+            "domain" => domain = Some(r.text()?),
+            "magnitude" => magnitude = Some(decimal(&mut r)?),
+            "payload" => payload = r.bytes()?,
+            "parts" => {
+                let n = r.expect(ARRAY)?;
+                if n == 0 || n > MAX_COMPOSITE_PARTS as u64 {
+                    return None;
+                }
+                part_count = n as usize;
+                for slot in &mut parts[..part_count] {
+                    *slot = part(&mut r)?;
+                }
+            }
+            #[cfg(feature = "corespec_signed_requests")]
+            "signature" => signature = Some(envelope(r.item()?)?),
+            _ => {
+                r.item()?;
+            }
+        }
+    }
+    if !r.rest.is_empty() || payload.len() > MAX_PAYLOAD_BYTES {
+        return None;
+    }
+
+    // As in JSON, a composite request ignores top-level domain fields.
+    if part_count == 0 {
+        parts[0] = ActionPart::new(domain?.as_bytes(), magnitude?);
+        part_count = 1;
+    }
+    Some(ActionRequest {
+        parts,
+        part_count,
+        payload_digest: *blake3::hash(payload).as_bytes(),
+        #[cfg(feature = "corespec_signed_requests")]
+        signature,
+    })
+}
+
+fn part(r: &mut Reader) -> Option<ActionPart> {
+    let (mut domain, mut magnitude) = (None, None);
+    for _ in 0..r.expect(MAP)? {
+        match r.text()? {
+            "domain" if domain.is_none() => domain = Some(r.text()?),
+            "magnitude" if magnitude.is_none() => magnitude = Some(decimal(r)?),
+            "domain" | "magnitude" => return None,
+            _ => {
+                r.item()?;
+            }
+        }
+    }
+    Some(ActionPart::new(domain?.as_bytes(), magnitude?))
+}
+
+/// `uint`, or `4([exponent, uint])` with at most `MAX_FRACTION_DIGITS`
+/// fraction digits.
+fn decimal(r: &mut Reader) -> Option<DecimalMagnitude> {
+    match r.head()? {
+        (UINT, int) => Some(DecimalMagnitude {
+            int,
+            frac: 0,
+            frac_digits: 0,
+        }),
+        (TAG, DECIMAL_FRACTION) => {
+            if r.expect(ARRAY)? != 2 {
+                return None;
+            }
+            // Exponent `-1 - n` is encoded as negative integer `n`.
+            let frac_digits = match r.head()? {
+                (UINT, 0) => 0,
+                (NINT, n) if n < MAX_FRACTION_DIGITS as u64 => n as u32 + 1,
+                _ => return None,
+            };
+            let mantissa = r.expect(UINT)?;
+            let scale = 10u64.pow(frac_digits);
+            Some(DecimalMagnitude {
+                int: mantissa / scale,
+                frac: mantissa % scale,
+                frac_digits,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// `(key, sig)` from a `{"key": bstr, "sig": bstr}` envelope, if well formed.
+#[cfg(feature = "corespec_signed_requests")]
+fn envelope(item: &[u8]) -> Option<([u8; 32], [u8; 64])> {
+    let mut r = Reader { rest: item };
+    let (mut key, mut sig) = (None, None);
+    for _ in 0..r.expect(MAP)? {
+        match r.text()? {
+            "key" if key.is_none() => key = Some(r.bytes()?.try_into().ok()?),
+            "sig" if sig.is_none() => sig = Some(r.bytes()?.try_into().ok()?),
+            "key" | "sig" => return None,
+            _ => {
+                r.item()?;
+            }
+        }
+    }
+    Some((key?, sig?))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::canonical::{canonical_bytes, CANONICAL_MAX_BYTES};
+    use crate::parse::parse_request;
+
+    //
+    // -------------------- Encoder (tests only) --------------------
+    //
+
+    /// Shortest-form head.
+    pub(crate) fn head(major: u8, arg: u64) -> Vec<u8> {
+        let m = major << 5;
+        match arg {
+            0..=23 => vec![m | arg as u8],
+            24..=0xff => vec![m | 24, arg as u8],
+            0x100..=0xffff => [&[m | 25][..], &(arg as u16).to_be_bytes()].concat(),
+            0x1_0000..=0xffff_ffff => [&[m | 26][..], &(arg as u32).to_be_bytes()].concat(),
+            _ => [&[m | 27][..], &arg.to_be_bytes()].concat(),
+        }
+    }
+
+    pub(crate) fn text(s: &str) -> Vec<u8> {
+        [head(TEXT, s.len() as u64), s.as_bytes().to_vec()].concat()
+    }
+
+    pub(crate) fn bytes(b: &[u8]) -> Vec<u8> {
+        [head(BYTES, b.len() as u64), b.to_vec()].concat()
+    }
+
+    pub(crate) fn uint(n: u64) -> Vec<u8> {
+        head(UINT, n)
+    }
+
+    /// `mantissa * 10^-digits` as a decimal fraction.
+    pub(crate) fn decimal_fraction(digits: u64, mantissa: u64) -> Vec<u8> {
+        let exponent = if digits == 0 {
+            uint(0)
+        } else {
+            head(NINT, digits - 1)
+        };
+        [
+            head(TAG, DECIMAL_FRACTION),
+            head(ARRAY, 2),
+            exponent,
+            uint(mantissa),
+        ]
+        .concat()
+    }
+
+    pub(crate) fn array(items: &[Vec<u8>]) -> Vec<u8> {
+        [head(ARRAY, items.len() as u64), items.concat()].concat()
+    }
+
+    pub(crate) fn map(entries: &[(&str, Vec<u8>)]) -> Vec<u8> {
+        let mut out = head(MAP, entries.len() as u64);
+        for (key, value) in entries {
+            out.extend(text(key));
+            out.extend_from_slice(value);
+        }
+        out
+    }
+
+    fn canonical(req: ActionRequest) -> Vec<u8> {
+        canonical_bytes(&req, &mut [0u8; CANONICAL_MAX_BYTES]).to_vec()
+    }
+
+    fn part_map(domain: &str, magnitude: Vec<u8>) -> Vec<u8> {
+        map(&[("domain", text(domain)), ("magnitude", magnitude)])
+    }
+
+    #[test]
+    fn equivalent_json_and_cbor_parse_to_the_same_request() {
+        let long = "d".repeat(80);
+        let pairs = [
+            (r#"{"domain":"test","magnitude":1}"#.to_string(), part_map("test", uint(1))),
+            (
+                r#"{"domain":"payment","magnitude":12.50}"#.to_string(),
+                part_map("payment", decimal_fraction(2, 1250)),
+            ),
+            (
+                r#"{"domain":"test","magnitude":0.000000000000000001}"#.to_string(),
+                part_map("test", decimal_fraction(18, 1)),
+            ),
+            (
+                r#"{"client":"x","magnitude":3,"domain":"deploy","payload":"eyJ0cmFuc2FjdGlvbiI6ICJURVNUIn0="}"#
+                    .to_string(),
+                map(&[
+                    ("client", map(&[("nested", array(&[uint(1), text("x")]))])),
+                    ("magnitude", uint(3)),
+                    ("domain", text("deploy")),
+                    ("payload", bytes(br#"{"transaction": "TEST"}"#)),
+                ]),
+            ),
+            (
+                r#"{"parts":[{"domain":"test","magnitude":1},{"domain":"payment","magnitude":0.5}]}"#
+                    .to_string(),
+                map(&[(
+                    "parts",
+                    array(&[
+                        part_map("test", uint(1)),
+                        part_map("payment", decimal_fraction(1, 5)),
+                    ]),
+                )]),
+            ),
+            // Over-long domains are cut the same way.
+            (
+                format!(r#"{{"domain":"{}","magnitude":7}}"#, long),
+                part_map(&long, uint(7)),
+            ),
+        ];
+        for (json, cbor) in pairs {
+            let from_json = canonical(parse_request(json.as_bytes()).unwrap());
+            let from_cbor = canonical(parse_request_cbor(&cbor).unwrap());
+            assert_eq!(from_cbor, from_json, "{}", json);
+        }
+    }
+
+    #[test]
+    fn magnitude_is_an_exact_unsigned_decimal() {
+        let magnitude = |m: Vec<u8>| {
+            parse_request_cbor(&part_map("test", m)).map(|req| req.parts()[0].magnitude)
+        };
+        assert_eq!(
+            magnitude(decimal_fraction(0, 42)),
+            Some(DecimalMagnitude {
+                int: 42,
+                frac: 0,
+                frac_digits: 0
+            })
+        );
+        assert_eq!(magnitude(uint(u64::MAX)).unwrap().int, u64::MAX);
+        for bad in [
+            head(NINT, 0),                            // -1
+            vec![0xf9, 0x3c, 0x00],                   // 1.0 as a half float
+            vec![0xfb, 0x3f, 0xf0, 0, 0, 0, 0, 0, 0], // 1.0 as a double
+            text("1"),
+            decimal_fraction(19, 1), // 19 fraction digits
+            [head(TAG, 4), head(ARRAY, 2), uint(3), uint(1)].concat(), // 1e3
+            [head(TAG, 4), head(ARRAY, 2), head(NINT, 0), head(NINT, 4)].concat(),
+            [head(TAG, 5), head(ARRAY, 2), head(NINT, 0), uint(15)].concat(), // bigfloat
+        ] {
+            assert_eq!(magnitude(bad.clone()), None, "{:02x?}", bad);
+        }
+    }
+
+    #[test]
+    fn shape_failures_are_none() {
+        let one = part_map("test", uint(1));
+        let listed = array(std::slice::from_ref(&one));
+        let rejected: Vec<Vec<u8>> = vec![
+            vec![],
+            one[..one.len() - 1].to_vec(),      // truncated
+            [one.clone(), vec![0x00]].concat(), // trailing item
+            listed.clone(),                     // not a map
+            map(&[("domain", text("test"))]),   // no magnitude
+            map(&[
+                ("domain", text("test")),
+                ("magnitude", uint(1)),
+                ("domain", text("x")),
+            ]),
+            [vec![0xa2, 0x01, 0x01], text("domain"), text("test")].concat(), // integer key
+            [
+                vec![0xbf],
+                text("domain"),
+                text("test"),
+                text("magnitude"),
+                uint(1),
+                vec![0xff],
+            ]
+            .concat(), // indefinite map
+            map(&[("domain", bytes(b"test")), ("magnitude", uint(1))]),
+            map(&[
+                ("domain", [head(TEXT, 2), vec![0xc3, 0x28]].concat()),
+                ("magnitude", uint(1)),
+            ]),
+            map(&[
+                ("domain", text("test")),
+                ("magnitude", uint(1)),
+                ("payload", text("AA==")),
+            ]),
+            map(&[("parts", array(&[]))]),
+            map(&[("parts", array(&vec![one.clone(); MAX_COMPOSITE_PARTS + 1]))]),
+            map(&[(
+                "parts",
+                array(&[one.clone(), map(&[("domain", text("test"))])]),
+            )]),
+            map(&[
+                ("parts", listed.clone()),
+                ("parts", listed),
+            ]),
+            // Claims far more entries than there are bytes.
+            [head(MAP, u64::MAX)].concat(),
+            map(&[
+                ("x", head(ARRAY, u64::MAX)),
+                ("domain", text("test")),
+                ("magnitude", uint(1)),
+            ]),
+            map(&[("x", head(MAP, u64::MAX / 2 + 1))]),
+        ];
+        for body in rejected {
+            assert!(parse_request_cbor(&body).is_none(), "{:02x?}", body);
+        }
+    }
+
+    #[test]
+    fn payload_is_hashed_within_limit() {
+        let with = |payload: &[u8]| {
+            map(&[
+                ("domain", text("test")),
+                ("magnitude", uint(1)),
+                ("payload", bytes(payload)),
+            ])
+        };
+        let req = parse_request_cbor(&with(&[7u8; MAX_PAYLOAD_BYTES])).unwrap();
+        assert_eq!(
+            req.payload_digest(),
+            blake3::hash(&[7u8; MAX_PAYLOAD_BYTES]).as_bytes()
+        );
+        assert!(parse_request_cbor(&with(&[7u8; MAX_PAYLOAD_BYTES + 1])).is_none());
+    }
+
+    #[test]
+    fn deep_unknown_values_are_skipped_without_recursion() {
+        // A million nested arrays would overflow a recursive decoder's stack.
+        let depth = 1_000_000;
+        let mut deep = vec![0x81u8; depth];
+        deep.push(0x00);
+        let body = map(&[
+            ("domain", text("test")),
+            ("magnitude", uint(1)),
+            ("x", deep.clone()),
+        ]);
+        assert_eq!(
+            parse_request_cbor(&body).unwrap().parts()[0].domain(),
+            b"test"
+        );
+        deep.pop();
+        let body = map(&[
+            ("domain", text("test")),
+            ("magnitude", uint(1)),
+            ("x", deep),
+        ]);
+        assert!(parse_request_cbor(&body).is_none());
+    }
+}
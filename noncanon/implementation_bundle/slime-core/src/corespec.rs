@@ -0,0 +1,134 @@
+//! CoreSpec constants — compile-time law.
+//!
+//! No runtime configuration. No env vars. Change these constants = produce
+//! a different binary = different CoreSpec.
+
+/// Upper bound on parts in one composite ActionRequest.
+/// Bounds every per-part loop at compile time (V1 §8).
+pub const MAX_COMPOSITE_PARTS: usize = 4;
+
+/// Fraction digits accepted in a decimal magnitude. Keeps unit scaling
+/// exact in `u128` (10^18 · u32::MAX < 2^128).
+pub const MAX_FRACTION_DIGITS: usize = 18;
+
+/// Bytes of a domain name kept from the request; longer names are cut and
+/// then fail resolution.
+pub const MAX_DOMAIN_BYTES: usize = 64;
+
+/// Largest decoded `payload` (v0 INGRESS_API_SPEC: 64 KB). Larger is a
+/// format failure.
+pub const MAX_PAYLOAD_BYTES: usize = 64 * 1024;
+
+/// Domain mapping table — sealed at compile time.
+/// Unknown domains are structurally impossible.
+pub const DOMAIN_TABLE: &[(&str, u16)] = &[
+    ("test", 0),
+    ("payment", 1),
+    ("deploy", 2),
+    ("db_prod", 3),
+];
+
+/// Weekly UTC windows — sealed at compile time.
+/// `(domain_id, day, start_minute, end_minute)`: day 0 = Monday, minutes
+/// since 00:00 UTC, half-open `[start, end)`. A domain listed here is
+/// IMPOSSIBLE outside all of its windows; unlisted domains are unconstrained.
+/// A window crossing midnight is declared as two entries.
+pub const TIME_WINDOWS: &[(u16, u8, u16, u16)] = &[
+    (2, 1, 2 * 60, 4 * 60), // deploy: Tuesday 02:00–04:00
+    (2, 3, 2 * 60, 4 * 60), // deploy: Thursday 02:00–04:00
+    (3, 6, 60, 3 * 60),     // db_prod: Sunday 01:00–03:00
+];
+
+#[derive(Clone, Copy)]
+pub enum Rounding {
+    Floor,
+    Ceil,
+    HalfUp,
+}
+
+/// Magnitude units — sealed at compile time.
+/// `(domain_id, scale, rounding)`: a decimal magnitude `m` becomes the
+/// integer `round(m · scale)` in the domain's unit before the law sees it.
+/// Unlisted domains use scale 1 with `Floor` (canon: floats round down).
+pub const DOMAIN_UNITS: &[(u16, u32, Rounding)] = &[
+    (1, 100, Rounding::Ceil), // payment: cents, partial cents round up
+    (3, 1, Rounding::HalfUp), // db_prod: whole units, halves round up
+];
+
+const fn domain_id_known(domain_id: u16) -> bool {
+    let mut i = 0;
+    while i < DOMAIN_TABLE.len() {
+        if DOMAIN_TABLE[i].1 == domain_id {
+            return true;
+        }
+        i += 1;
+    }
+    false
+}
+
+// Build fails on a malformed window or unit, or one naming an unknown
+// domain (V1 §7.1).
+const _: () = {
+    let mut i = 0;
+    while i < TIME_WINDOWS.len() {
+        let (domain_id, day, start, end) = TIME_WINDOWS[i];
+        assert!(day < 7, "TIME_WINDOWS: day out of range");
+        assert!(start < end && end <= 24 * 60, "TIME_WINDOWS: empty or overflowing window");
+        assert!(domain_id_known(domain_id), "TIME_WINDOWS: unknown domain id");
+        i += 1;
+    }
+    let mut i = 0;
+    while i < DOMAIN_UNITS.len() {
+        let (domain_id, scale, _) = DOMAIN_UNITS[i];
+        assert!(scale > 0, "DOMAIN_UNITS: zero scale");
+        assert!(domain_id_known(domain_id), "DOMAIN_UNITS: unknown domain id");
+        i += 1;
+    }
+};
+
+/// Budget constants — fresh budget per request (V1 statelessness).
+/// No state persists between requests.
+#[cfg(not(feature = "corespec_saturating"))]
+pub const CORESPEC_CAPACITY: u32 = 10_000;
+#[cfg(not(feature = "corespec_saturating"))]
+pub const CORESPEC_PROGRESSION: u32 = 1;
+
+/// Saturating CoreSpec — one budget for the whole process lifetime.
+/// Capacity only decreases; below the threshold the ledger is SATURATED,
+/// once exhausted it is SEALED until restart (v0 INGRESS_API_SPEC §Rate Limiting).
+#[cfg(feature = "corespec_saturating")]
+pub const CORESPEC_CAPACITY: u32 = 10_000;
+#[cfg(feature = "corespec_saturating")]
+pub const CORESPEC_PROGRESSION: u32 = 1_000;
+#[cfg(feature = "corespec_saturating")]
+pub const CORESPEC_SATURATION_THRESHOLD: u32 = 1_000;
+
+/// Unix-socket ingress CoreSpec — who may connect, by `SO_PEERCRED`.
+/// A client is admitted if its uid is listed in `INGRESS_PEER_UIDS` or its
+/// gid in `INGRESS_PEER_GIDS`; every other client gets IMPOSSIBLE. Empty
+/// lists admit no one: list the local agent's ids here.
+#[cfg(feature = "corespec_ingress_unix")]
+pub const INGRESS_PEER_UIDS: &[u32] = &[];
+#[cfg(feature = "corespec_ingress_unix")]
+pub const INGRESS_PEER_GIDS: &[u32] = &[];
+
+/// TLS ingress CoreSpec — TLS 1.3 terminated in the runner on
+/// `127.0.0.1:8080`. The server chain (DER, leaf first) and its PKCS#8 DER
+/// key are embedded at build time, e.g.
+/// `&[include_bytes!("../../tls/server.der")]`. A client must present a
+/// chain through a CA whose certificate's SHA-256 is listed in
+/// `TLS_CLIENT_CA_SHA256`; every other handshake is refused. The reference
+/// embeds nothing: the runner refuses to boot until a certificate and key
+/// are filled in, and no pins admit no one.
+#[cfg(feature = "corespec_ingress_tls")]
+pub const TLS_SERVER_CERT_CHAIN: &[&[u8]] = &[];
+#[cfg(feature = "corespec_ingress_tls")]
+pub const TLS_SERVER_KEY: &[u8] = &[];
+#[cfg(feature = "corespec_ingress_tls")]
+pub const TLS_CLIENT_CA_SHA256: &[[u8; 32]] = &[];
+
+/// Signed-request CoreSpec — Ed25519 public keys of the approved agents.
+/// A request must carry a signature by one of them over its canonical
+/// bytes (`signature`). No keys: no request is admitted.
+#[cfg(feature = "corespec_signed_requests")]
+pub const REQUEST_SIGNING_KEYS: &[[u8; 32]] = &[];
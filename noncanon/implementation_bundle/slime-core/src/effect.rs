@@ -0,0 +1,14 @@
+//! From a law decision to an egress effect. The frame layout itself is
+//! `slime-abi`'s.
+
+use crate::law::{domain_to_egress_id, LawPart};
+use slime_abi::AuthorizedEffect;
+
+/// The effect of `part` once the law applied `applied` to it.
+pub fn authorized_effect(part: &LawPart, applied: u32) -> AuthorizedEffect {
+    AuthorizedEffect {
+        domain_id: domain_to_egress_id(part.domain),
+        magnitude: applied as u64,
+        actuation_token: 0u128,
+    }
+}
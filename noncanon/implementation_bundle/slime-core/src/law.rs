@@ -0,0 +1,419 @@
+//! Domain resolution, magnitude units, time windows and the law step.
+//!
+//! `admit` turns a parsed request into parts the law can see: every domain
+//! resolved through the sealed table, every magnitude scaled into its
+//! domain's unit. `resolve_composite` then decides all parts as one unit.
+//! Reasons (`Rejection`, `Refusal`) are for the host's audit trail only
+//! (R-1); a client sees AUTHORIZED or IMPOSSIBLE.
+
+use crate::corespec::{
+    Rounding, CORESPEC_CAPACITY, CORESPEC_PROGRESSION, DOMAIN_TABLE, DOMAIN_UNITS,
+    MAX_COMPOSITE_PARTS, TIME_WINDOWS,
+};
+use crate::parse::{ActionRequest, DecimalMagnitude};
+
+// Real AB-S engine (private dependency, not shipped with open-source SLIME)
+#[cfg(feature = "real_ab")]
+use anathema_breaker_core::pom::resolve_action::resolve_action;
+#[cfg(feature = "real_ab")]
+use anathema_breaker_core::pom::topology::Action as AbAction;
+#[cfg(feature = "real_ab")]
+use anathema_breaker_core::pom::topology::RZ;
+#[cfg(feature = "real_ab")]
+pub use anathema_breaker_core::pom::types::{Budget, Capacity, Domain, Magnitude, Progression};
+
+// Stub resolver (default for open-source builds)
+#[cfg(feature = "stub_ab")]
+use crate::stub_resolver::{resolve_action, Action as AbAction, RZ};
+#[cfg(feature = "stub_ab")]
+pub use crate::stub_resolver::{Budget, Capacity, Domain, Magnitude, Progression};
+
+/// A part that passed domain and magnitude checks, ready for the law.
+#[derive(Clone, Copy)]
+pub struct LawPart {
+    pub domain: Domain,
+    pub magnitude: u32,
+}
+
+/// Every part of one request, admitted.
+#[derive(Clone, Copy)]
+pub struct LawRequest {
+    pub parts: [LawPart; MAX_COMPOSITE_PARTS],
+    pub count: usize,
+}
+
+impl LawRequest {
+    pub fn parts(&self) -> &[LawPart] {
+        &self.parts[..self.count]
+    }
+}
+
+/// Why a part was not admitted to the law. Internal only.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Rejection {
+    UnknownDomain,
+    /// Two parts of one composite name the same domain.
+    DuplicateDomain { domain_id: u64 },
+    /// Zero, or too large for `u32`, once scaled into the domain's unit.
+    MagnitudeRange {
+        domain_id: u64,
+        written: DecimalMagnitude,
+    },
+}
+
+/// Why the law step refused. Internal only: feeds the audit trail (R-1),
+/// never the client response.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Refusal {
+    TimeWindowClosed,
+    Impossible,
+    #[cfg(feature = "corespec_saturating")]
+    Sealed,
+}
+
+//
+// -------------------- Domain Resolution (Phase 6.3) --------------------
+//
+
+pub fn resolve_domain(name: &str) -> Option<Domain> {
+    for &(key, id) in DOMAIN_TABLE {
+        if key == name {
+            return Some(Domain(id));
+        }
+    }
+    None
+}
+
+pub fn domain_to_egress_id(d: Domain) -> u64 {
+    d.0 as u64
+}
+
+//
+// -------------------- Magnitude Units (CoreSpec) --------------------
+//
+
+fn domain_unit(domain: Domain) -> (u32, Rounding) {
+    for &(domain_id, scale, rounding) in DOMAIN_UNITS {
+        if domain_id == domain.0 {
+            return (scale, rounding);
+        }
+    }
+    (1, Rounding::Floor)
+}
+
+/// Convert a decimal magnitude into the domain's integer unit.
+/// Exact: `u128` holds every intermediate. `None` if the result
+/// does not fit `u32` (AB-S uses Magnitude(u32)).
+pub fn quantize(domain: Domain, m: DecimalMagnitude) -> Option<u32> {
+    let (scale, rounding) = domain_unit(domain);
+    let denom = 10u128.pow(m.frac_digits);
+    let frac_scaled = m.frac as u128 * scale as u128;
+    let (q, r) = (frac_scaled / denom, frac_scaled % denom);
+    let round_up = match rounding {
+        Rounding::Floor => false,
+        Rounding::Ceil => r > 0,
+        Rounding::HalfUp => 2 * r >= denom,
+    };
+    let scaled = m.int as u128 * scale as u128 + q + round_up as u128;
+    u32::try_from(scaled).ok()
+}
+
+/// Resolve every domain and scale every magnitude; the first part that
+/// fails rejects the whole request. A domain may appear in one part only.
+pub fn admit(req: &ActionRequest) -> Result<LawRequest, Rejection> {
+    let mut law = LawRequest {
+        parts: [LawPart {
+            domain: Domain(0),
+            magnitude: 0,
+        }; MAX_COMPOSITE_PARTS],
+        count: req.parts().len(),
+    };
+    for (i, part) in req.parts().iter().enumerate() {
+        // 1. Resolve domain via sealed compile-time table
+        let name = core::str::from_utf8(part.domain()).unwrap_or("");
+        let domain = resolve_domain(name).ok_or(Rejection::UnknownDomain)?;
+        let domain_id = domain_to_egress_id(domain);
+        if law.parts[..i].iter().any(|p| p.domain.0 == domain.0) {
+            return Err(Rejection::DuplicateDomain { domain_id });
+        }
+
+        // 2. Scale into the domain's unit; must be non-zero and fit u32
+        //    (AB-S uses Magnitude(u32))
+        let magnitude = match quantize(domain, part.magnitude) {
+            Some(m) if m > 0 => m,
+            _ => {
+                return Err(Rejection::MagnitudeRange {
+                    domain_id,
+                    written: part.magnitude,
+                })
+            }
+        };
+
+        law.parts[i] = LawPart { domain, magnitude };
+    }
+    Ok(law)
+}
+
+//
+// -------------------- Time Windows (CoreSpec) --------------------
+//
+
+/// Position within the UTC week. The host reads its clock once per
+/// request and hands the result to the law step.
+#[derive(Clone, Copy)]
+pub struct WeekTime {
+    pub day: u8,
+    pub minute: u16,
+}
+
+impl WeekTime {
+    pub fn from_unix(secs: u64) -> Self {
+        let days = secs / 86_400;
+        // 1970-01-01 was a Thursday (day 3 with Monday = 0).
+        let day = ((days + 3) % 7) as u8;
+        let minute = ((secs % 86_400) / 60) as u16;
+        Self { day, minute }
+    }
+}
+
+/// True if `domain` is unconstrained, or `now` falls in one of its windows.
+/// An unreadable clock closes every constrained domain (fail-closed).
+pub fn time_window_open(domain: Domain, now: Option<WeekTime>) -> bool {
+    let mut constrained = false;
+    for &(domain_id, day, start, end) in TIME_WINDOWS {
+        if domain_id != domain.0 {
+            continue;
+        }
+        constrained = true;
+        if let Some(t) = now {
+            if t.day == day && t.minute >= start && t.minute < end {
+                return true;
+            }
+        }
+    }
+    !constrained
+}
+
+//
+// -------------------- Law Resolution Wrapper --------------------
+//
+
+/// Resolve an action through the selected law engine.
+/// Returns the applied magnitude on AUTHORIZED, or None on IMPOSSIBLE.
+pub fn resolve_law(domain: Domain, magnitude: Magnitude, budget: &mut Budget) -> Option<u32> {
+    let action = AbAction::<RZ>::new(domain, magnitude);
+    match resolve_action(action, budget) {
+        Ok(effect) => Some(effect.magnitude_applied.0),
+        Err(_impossibility) => None,
+    }
+}
+
+/// Resolve every part of a composite action as one unit.
+/// The request gets one fresh budget (V1 statelessness) with a
+/// progression step per part, and every part draws on its capacity: the
+/// parts together may not apply more than a single action could. The
+/// applied magnitudes are returned only if every part is AUTHORIZED.
+#[cfg(not(feature = "corespec_saturating"))]
+pub fn resolve_composite(
+    parts: &[LawPart],
+    now: Option<WeekTime>,
+) -> Result<[u32; MAX_COMPOSITE_PARTS], Refusal> {
+    if !parts.iter().all(|p| time_window_open(p.domain, now)) {
+        return Err(Refusal::TimeWindowClosed);
+    }
+    let steps = CORESPEC_PROGRESSION.saturating_mul(parts.len() as u32);
+    let mut budget = budget_from(CORESPEC_CAPACITY, steps);
+    let mut applied = [0u32; MAX_COMPOSITE_PARTS];
+    for (slot, part) in applied.iter_mut().zip(parts) {
+        *slot = resolve_law(part.domain, Magnitude(part.magnitude), &mut budget)
+            .ok_or(Refusal::Impossible)?;
+    }
+    Ok(applied)
+}
+
+pub(crate) fn budget_from(capacity: u32, progression: u32) -> Budget {
+    #[cfg(feature = "real_ab")]
+    {
+        Budget {
+            capacity: Capacity(capacity),
+            progression: Progression(progression),
+        }
+    }
+    #[cfg(feature = "stub_ab")]
+    {
+        Budget::new(Capacity(capacity), Progression(progression))
+    }
+}
+
+/// Budget as declared by the CoreSpec constants.
+#[cfg(feature = "corespec_saturating")]
+pub(crate) fn corespec_budget() -> Budget {
+    budget_from(CORESPEC_CAPACITY, CORESPEC_PROGRESSION)
+}
+
+/// Remaining `(capacity, progression)` of a budget, read-only.
+#[cfg(feature = "corespec_saturating")]
+pub(crate) fn budget_remaining(budget: &Budget) -> (u32, u32) {
+    #[cfg(feature = "real_ab")]
+    {
+        (budget.capacity.0, budget.progression.0)
+    }
+    #[cfg(feature = "stub_ab")]
+    {
+        (budget.capacity().0, budget.progression().0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{parse_decimal, parse_request};
+
+    #[test]
+    fn resolve_domain_known() {
+        assert!(resolve_domain("test").is_some());
+        assert!(resolve_domain("payment").is_some());
+        assert!(resolve_domain("deploy").is_some());
+        assert!(resolve_domain("db_prod").is_some());
+    }
+
+    #[test]
+    fn resolve_domain_unknown() {
+        assert!(resolve_domain("unknown").is_none());
+        assert!(resolve_domain("").is_none());
+        assert!(resolve_domain("PAYMENT").is_none());
+    }
+
+    // 2026-10-20 is a Tuesday.
+    const TUE_0230_UTC: u64 = 1_792_463_400;
+    const TUE_0400_UTC: u64 = 1_792_468_800;
+    const SUN_0100_UTC: u64 = 1_792_890_000;
+
+    #[test]
+    fn week_time_from_unix_known_answers() {
+        let epoch = WeekTime::from_unix(0);
+        assert_eq!((epoch.day, epoch.minute), (3, 0));
+        let t = WeekTime::from_unix(TUE_0230_UTC);
+        assert_eq!((t.day, t.minute), (1, 150));
+        let t = WeekTime::from_unix(SUN_0100_UTC);
+        assert_eq!((t.day, t.minute), (6, 60));
+    }
+
+    #[test]
+    fn time_window_gates_constrained_domains_only() {
+        let inside = Some(WeekTime::from_unix(TUE_0230_UTC));
+        let edge = Some(WeekTime::from_unix(TUE_0400_UTC));
+        let deploy = resolve_domain("deploy").unwrap();
+        let db_prod = resolve_domain("db_prod").unwrap();
+        let payment = resolve_domain("payment").unwrap();
+
+        assert!(time_window_open(deploy, inside));
+        assert!(!time_window_open(deploy, edge));
+        assert!(!time_window_open(db_prod, inside));
+        assert!(time_window_open(db_prod, Some(WeekTime::from_unix(SUN_0100_UTC))));
+        assert!(time_window_open(payment, edge));
+    }
+
+    #[test]
+    fn unreadable_clock_closes_constrained_domains() {
+        assert!(!time_window_open(resolve_domain("deploy").unwrap(), None));
+        assert!(time_window_open(resolve_domain("test").unwrap(), None));
+    }
+
+    #[cfg(not(feature = "corespec_saturating"))]
+    #[test]
+    fn law_step_applies_time_windows_with_fake_clock() {
+        let part = |domain, magnitude| LawPart {
+            domain: resolve_domain(domain).unwrap(),
+            magnitude,
+        };
+        let inside = Some(WeekTime::from_unix(TUE_0230_UTC));
+        let outside = Some(WeekTime::from_unix(TUE_0400_UTC));
+        assert!(resolve_composite(&[part("deploy", 5)], inside).is_ok());
+        assert_eq!(
+            resolve_composite(&[part("deploy", 5)], outside),
+            Err(Refusal::TimeWindowClosed)
+        );
+        // One closed part makes the whole composite IMPOSSIBLE.
+        let parts = [part("deploy", 5), part("db_prod", 5)];
+        assert!(resolve_composite(&parts, inside).is_err());
+    }
+
+    fn q(domain: &str, magnitude: &str) -> Option<u32> {
+        quantize(resolve_domain(domain).unwrap(), parse_decimal(magnitude)?)
+    }
+
+    #[test]
+    fn quantize_known_answers() {
+        // Default unit: scale 1, floor (canon truncation).
+        assert_eq!(q("deploy", "3"), Some(3));
+        assert_eq!(q("deploy", "2.999"), Some(2));
+        assert_eq!(q("test", "0.9"), Some(0));
+        // payment: cents, ceil.
+        assert_eq!(q("payment", "12.34"), Some(1234));
+        assert_eq!(q("payment", "12.341"), Some(1235));
+        assert_eq!(q("payment", "0.000000000000000001"), Some(1));
+        assert_eq!(q("payment", "7"), Some(700));
+        // db_prod: whole units, half up.
+        assert_eq!(q("db_prod", "2.5"), Some(3));
+        assert_eq!(q("db_prod", "2.4999"), Some(2));
+    }
+
+    #[test]
+    fn quantize_rejects_u32_overflow_after_scaling() {
+        assert_eq!(q("test", "4294967295"), Some(u32::MAX));
+        assert_eq!(q("test", "4294967296"), None);
+        assert_eq!(q("test", "4294967295.99"), Some(u32::MAX));
+        assert_eq!(q("payment", "42949672.95"), Some(u32::MAX));
+        assert_eq!(q("payment", "42949672.951"), None);
+        assert_eq!(q("payment", "42949673"), None);
+        assert_eq!(q("payment", "18446744073709551615.99"), None);
+    }
+
+    #[test]
+    fn admit_rejects_at_the_first_failing_part() {
+        let admit_body = |body: &[u8]| admit(&parse_request(body).unwrap());
+
+        let both = br#"{"parts":[{"domain":"test","magnitude":1},{"domain":"payment","magnitude":0.5}]}"#;
+        let law = admit_body(both).unwrap();
+        let admitted: Vec<(u64, u32)> = law
+            .parts()
+            .iter()
+            .map(|p| (domain_to_egress_id(p.domain), p.magnitude))
+            .collect();
+        assert_eq!(admitted, [(0, 1), (1, 50)]);
+
+        let unknown = br#"{"parts":[{"domain":"test","magnitude":1},{"domain":"nope","magnitude":1}]}"#;
+        assert_eq!(admit_body(unknown).err(), Some(Rejection::UnknownDomain));
+        let twice = br#"{"parts":[{"domain":"payment","magnitude":1},{"domain":"test","magnitude":1},{"domain":"payment","magnitude":2}]}"#;
+        assert_eq!(
+            admit_body(twice).err(),
+            Some(Rejection::DuplicateDomain { domain_id: 1 })
+        );
+        assert_eq!(
+            admit_body(br#"{"domain":"payment","magnitude":42949673}"#).err(),
+            Some(Rejection::MagnitudeRange {
+                domain_id: 1,
+                written: parse_decimal("42949673").unwrap(),
+            })
+        );
+    }
+
+    #[cfg(not(feature = "corespec_saturating"))]
+    #[test]
+    fn composite_parts_share_one_capacity() {
+        let resolve = |body: &[u8]| {
+            let law = admit(&parse_request(body).unwrap()).unwrap();
+            resolve_composite(law.parts(), None)
+        };
+        // test is in whole units, payment in cents: 10_000 + 1 > capacity.
+        let over = br#"{"parts":[{"domain":"test","magnitude":10000},{"domain":"payment","magnitude":0.01}]}"#;
+        assert_eq!(resolve(over).err(), Some(Refusal::Impossible));
+        let half = br#"{"parts":[{"domain":"test","magnitude":6000},{"domain":"payment","magnitude":60}]}"#;
+        assert_eq!(resolve(half).err(), Some(Refusal::Impossible));
+        let within = br#"{"parts":[{"domain":"test","magnitude":9999},{"domain":"payment","magnitude":0.01}]}"#;
+        assert_eq!(resolve(within).unwrap()[..2], [9999, 1]);
+        let single = br#"{"domain":"test","magnitude":10000}"#;
+        assert_eq!(resolve(single).unwrap()[0], 10000);
+    }
+}
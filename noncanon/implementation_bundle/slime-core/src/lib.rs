@@ -0,0 +1,47 @@
+//! SLIME law membrane, without I/O.
+//!
+//! Everything between the bytes of a request body and an egress effect:
+//! `parse_request` or `parse_request_cbor` (and, with
+//! `corespec_signed_requests`, the `signature` check over
+//! `canonical_bytes`), domain and magnitude admission (`admit`), law
+//! resolution (`resolve_composite`, or the lifetime `saturation::Ledger`)
+//! and `authorized_effect`. No sockets, no clock, no allocator: hosts
+//! supply the body and the time, and write the frames (`slime-abi`).
+//! `slime-runner` is one such host.
+
+#![cfg_attr(not(test), no_std)]
+
+// Compile-time guard: exactly one resolver must be selected.
+#[cfg(all(feature = "stub_ab", feature = "real_ab"))]
+compile_error!("Features `stub_ab` and `real_ab` are mutually exclusive");
+
+#[cfg(not(any(feature = "stub_ab", feature = "real_ab")))]
+compile_error!("Either `stub_ab` or `real_ab` feature must be enabled");
+
+// At most one ingress variant.
+#[cfg(all(feature = "corespec_ingress_unix", feature = "corespec_ingress_tls"))]
+compile_error!("Features `corespec_ingress_unix` and `corespec_ingress_tls` are mutually exclusive");
+
+mod canonical;
+mod cbor;
+pub mod corespec;
+mod effect;
+mod law;
+mod parse;
+#[cfg(feature = "corespec_saturating")]
+pub mod saturation;
+#[cfg(feature = "corespec_signed_requests")]
+pub mod signature;
+#[cfg(feature = "stub_ab")]
+pub mod stub_resolver;
+
+pub use canonical::{canonical_bytes, request_digest, CANONICAL_MAX_BYTES};
+pub use cbor::parse_request_cbor;
+pub use effect::authorized_effect;
+#[cfg(not(feature = "corespec_saturating"))]
+pub use law::resolve_composite;
+pub use law::{
+    admit, domain_to_egress_id, quantize, resolve_domain, resolve_law, time_window_open, Budget,
+    Capacity, Domain, LawPart, LawRequest, Magnitude, Progression, Refusal, Rejection, WeekTime,
+};
+pub use parse::{parse_decimal, parse_request, ActionPart, ActionRequest, DecimalMagnitude};
@@ -0,0 +1,390 @@
+//! Request parse — JSON body bytes to an `ActionRequest`.
+//!
+//! Reads `domain`, `magnitude` and `payload`; every other field is ignored.
+//! `cbor::parse_request_cbor` reads the same fields from CBOR into the same
+//! `ActionRequest`, so both encodings meet the same admission and law.
+//! Any shape it does not recognise is `None`, which the host reports as
+//! IMPOSSIBLE like every other refusal.
+//!
+//! A key may have whitespace before its colon, as JSON allows. String
+//! escapes are format failures: no field read here needs one, and an
+//! escaped key would be a field the parser cannot see. So is a key read
+//! here written twice in one object, as in CBOR: JSON leaves the winner to
+//! each parser, and this one would see a different request than others.
+//!
+//! The payload is opaque: it is base64-decoded only to be hashed, chunk by
+//! chunk, and never stored.
+
+use crate::corespec::{
+    MAX_COMPOSITE_PARTS, MAX_DOMAIN_BYTES, MAX_FRACTION_DIGITS, MAX_PAYLOAD_BYTES,
+};
+
+#[derive(Clone, Copy)]
+pub struct ActionPart {
+    pub domain: [u8; MAX_DOMAIN_BYTES],
+    pub domain_len: usize,
+    pub magnitude: DecimalMagnitude,
+}
+
+impl ActionPart {
+    pub fn domain(&self) -> &[u8] {
+        &self.domain[..self.domain_len]
+    }
+
+    /// Placeholder for unused slots in `ActionRequest::parts`.
+    pub(crate) const EMPTY: ActionPart = ActionPart {
+        domain: [0u8; MAX_DOMAIN_BYTES],
+        domain_len: 0,
+        magnitude: DecimalMagnitude {
+            int: 0,
+            frac: 0,
+            frac_digits: 0,
+        },
+    };
+
+    /// Domain bytes beyond `MAX_DOMAIN_BYTES` are cut; the cut name then
+    /// fails domain lookup like any other unknown one.
+    pub(crate) fn new(domain_bytes: &[u8], magnitude: DecimalMagnitude) -> Self {
+        let mut domain = [0u8; MAX_DOMAIN_BYTES];
+        let domain_len = domain_bytes.len().min(MAX_DOMAIN_BYTES);
+        domain[..domain_len].copy_from_slice(&domain_bytes[..domain_len]);
+        ActionPart {
+            domain,
+            domain_len,
+            magnitude,
+        }
+    }
+}
+
+/// Magnitude exactly as written by the client, before unit scaling:
+/// `int.frac` with `frac_digits` digits after the point.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DecimalMagnitude {
+    pub int: u64,
+    pub frac: u64,
+    pub frac_digits: u32,
+}
+
+/// One or more parts, authorized together or not at all.
+/// A plain `{"domain", "magnitude"}` body is a request with one part.
+pub struct ActionRequest {
+    pub(crate) parts: [ActionPart; MAX_COMPOSITE_PARTS],
+    pub(crate) part_count: usize,
+    pub(crate) payload_digest: [u8; 32],
+    /// `(key, sig)` from the request's signature envelope, if well formed.
+    #[cfg(feature = "corespec_signed_requests")]
+    pub(crate) signature: Option<([u8; 32], [u8; 64])>,
+}
+
+impl ActionRequest {
+    pub fn parts(&self) -> &[ActionPart] {
+        &self.parts[..self.part_count]
+    }
+
+    /// BLAKE3 of the decoded payload; an omitted payload is empty.
+    pub fn payload_digest(&self) -> &[u8; 32] {
+        &self.payload_digest
+    }
+}
+
+pub fn parse_request(body: &[u8]) -> Option<ActionRequest> {
+    let text = core::str::from_utf8(body).ok()?;
+    if text.contains('\\') {
+        return None;
+    }
+    let payload_digest = match field(text, "payload")? {
+        Some(value) => payload_digest(value)?,
+        None => *blake3::hash(b"").as_bytes(),
+    };
+    #[cfg(feature = "corespec_signed_requests")]
+    let signature = match field(text, "signature")? {
+        Some(value) => Some(crate::signature::json_envelope(value)?),
+        None => None,
+    };
+    let mut parts = [ActionPart::EMPTY; MAX_COMPOSITE_PARTS];
+
+    // Composite form: {"parts":[{"domain":..,"magnitude":..}, ...]}
+    if let Some(value) = field(text, "parts")? {
+        let s = value.trim_start().strip_prefix('[')?;
+        let mut list = &s[..s.find(']')?];
+        let mut part_count = 0;
+        while let Some(o) = list.find('{') {
+            if part_count == MAX_COMPOSITE_PARTS {
+                return None;
+            }
+            let c = list[o..].find('}')? + o;
+            parts[part_count] = parse_part(&list[o + 1..c])?;
+            part_count += 1;
+            list = &list[c + 1..];
+        }
+        if part_count == 0 {
+            return None;
+        }
+        return Some(ActionRequest {
+            parts,
+            part_count,
+            payload_digest,
+            #[cfg(feature = "corespec_signed_requests")]
+            signature,
+        });
+    }
+
+    parts[0] = parse_part(text)?;
+    Some(ActionRequest {
+        parts,
+        part_count: 1,
+        payload_digest,
+        #[cfg(feature = "corespec_signed_requests")]
+        signature,
+    })
+}
+
+/// BLAKE3 of a string literal in standard, padded base64. Anything else,
+/// including non-zero pad bits, or more than `MAX_PAYLOAD_BYTES` decoded,
+/// is `None`: one payload, one spelling.
+fn payload_digest(s: &str) -> Option<[u8; 32]> {
+    fn sextet(c: u8) -> Option<u32> {
+        let v = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return None,
+        };
+        Some(v as u32)
+    }
+
+    let s = s.trim_start().strip_prefix('"')?;
+    let encoded = &s.as_bytes()[..s.find('"')?];
+    if encoded.len() % 4 != 0 || encoded.len() / 4 * 3 > MAX_PAYLOAD_BYTES + 2 {
+        return None;
+    }
+
+    let mut hasher = blake3::Hasher::new();
+    let mut chunk = [0u8; 192];
+    let mut filled = 0;
+    let mut decoded = 0;
+    let last = (encoded.len() / 4).saturating_sub(1);
+    for (i, quad) in encoded.chunks_exact(4).enumerate() {
+        let pad = quad.iter().rev().take_while(|&&c| c == b'=').count();
+        if pad > 2 || (pad > 0 && i != last) {
+            return None;
+        }
+        let mut v = 0u32;
+        for &c in &quad[..4 - pad] {
+            v = v << 6 | sextet(c)?;
+        }
+        v <<= 6 * pad;
+        // Bits below the last whole byte must be zero.
+        if v & ((1 << (8 * pad)) - 1) != 0 {
+            return None;
+        }
+        let n = 3 - pad;
+        chunk[filled..filled + n].copy_from_slice(&v.to_be_bytes()[1..1 + n]);
+        filled += n;
+        decoded += n;
+        if filled == chunk.len() {
+            hasher.update(&chunk);
+            filled = 0;
+        }
+    }
+    if decoded > MAX_PAYLOAD_BYTES {
+        return None;
+    }
+    hasher.update(&chunk[..filled]);
+    Some(*hasher.finalize().as_bytes())
+}
+
+/// The text after the colon of key `name`, whitespace allowed before the
+/// colon: `Some(None)` if there is no such key, `None` if it appears
+/// twice. A quoted `name` with no colon after it is a value, not the key.
+/// With escapes refused, a quote always delimits a string, so what is
+/// found is a key.
+pub(crate) fn field<'a>(text: &'a str, name: &str) -> Option<Option<&'a str>> {
+    let mut found = None;
+    let mut from = 0;
+    while let Some(p) = text[from..].find(name) {
+        let (start, end) = (from + p, from + p + name.len());
+        from = end;
+        if !text[..start].ends_with('"') || !text[end..].starts_with('"') {
+            continue;
+        }
+        if let Some(value) = text[end + 1..].trim_start().strip_prefix(':') {
+            if found.is_some() {
+                return None;
+            }
+            found = Some(value);
+        }
+    }
+    Some(found)
+}
+
+fn parse_part(text: &str) -> Option<ActionPart> {
+    let domain_str = {
+        let s = field(text, "domain")??.trim_start().strip_prefix('"')?;
+        &s[..s.find('"')?]
+    };
+    let magnitude = parse_decimal(field(text, "magnitude")??)?;
+
+    Some(ActionPart::new(domain_str.as_bytes(), magnitude))
+}
+
+/// Non-negative decimal literal: `digits[.digits]`. Signs and exponents
+/// are format failures; scaling happens later, once the domain is known.
+pub fn parse_decimal(s: &str) -> Option<DecimalMagnitude> {
+    let s = s.trim_start();
+    let int_len = s.bytes().take_while(u8::is_ascii_digit).count();
+    if int_len == 0 {
+        return None;
+    }
+    let int = s[..int_len].parse::<u64>().ok()?;
+    let mut rest = &s[int_len..];
+
+    let (mut frac, mut frac_digits) = (0, 0);
+    if let Some(f) = rest.strip_prefix('.') {
+        let n = f.bytes().take_while(u8::is_ascii_digit).count();
+        if n == 0 || n > MAX_FRACTION_DIGITS {
+            return None;
+        }
+        frac = f[..n].parse::<u64>().ok()?;
+        frac_digits = n as u32;
+        rest = &f[n..];
+    }
+    if rest.starts_with(['e', 'E']) {
+        return None;
+    }
+
+    Some(DecimalMagnitude {
+        int,
+        frac,
+        frac_digits,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_decimal_rejects_non_plain_literals() {
+        assert!(parse_decimal("-1").is_none());
+        assert!(parse_decimal(".5").is_none());
+        assert!(parse_decimal("1.").is_none());
+        assert!(parse_decimal("1e3").is_none());
+        assert!(parse_decimal("1.5E3").is_none());
+        assert!(parse_decimal("0.0000000000000000001").is_none());
+        assert!(parse_decimal("18446744073709551616").is_none());
+        assert_eq!(
+            parse_decimal(" 12.050}"),
+            Some(DecimalMagnitude {
+                int: 12,
+                frac: 50,
+                frac_digits: 3
+            })
+        );
+    }
+
+    #[test]
+    fn parse_request_single_form_is_one_part() {
+        let req = parse_request(br#"{"domain":"deploy","magnitude":3}"#).unwrap();
+        assert_eq!(req.parts().len(), 1);
+        assert_eq!(req.parts()[0].domain(), b"deploy");
+        assert_eq!(req.parts()[0].magnitude, parse_decimal("3").unwrap());
+    }
+
+    #[test]
+    fn parse_request_composite_form() {
+        let body = br#"{"parts":[{"domain":"deploy","magnitude":1}, {"domain":"db_prod","magnitude":2}]}"#;
+        let req = parse_request(body).unwrap();
+        assert_eq!(req.parts().len(), 2);
+        assert_eq!(req.parts()[0].domain(), b"deploy");
+        assert_eq!(req.parts()[1].domain(), b"db_prod");
+        assert_eq!(req.parts()[1].magnitude, parse_decimal("2").unwrap());
+    }
+
+    #[test]
+    fn parse_request_composite_rejects_empty_and_oversized() {
+        assert!(parse_request(br#"{"parts":[]}"#).is_none());
+        let part = r#"{"domain":"test","magnitude":1}"#;
+        let body = format!("{{\"parts\":[{}]}}", [part; MAX_COMPOSITE_PARTS + 1].join(","));
+        assert!(parse_request(body.as_bytes()).is_none());
+        let body = format!("{{\"parts\":[{}]}}", [part; MAX_COMPOSITE_PARTS].join(","));
+        assert_eq!(parse_request(body.as_bytes()).unwrap().parts().len(), MAX_COMPOSITE_PARTS);
+    }
+
+    #[test]
+    fn payload_is_decoded_and_hashed() {
+        let req = parse_request(br#"{"domain":"test","magnitude":1}"#).unwrap();
+        assert_eq!(req.payload_digest(), blake3::hash(b"").as_bytes());
+        let body = br#"{"domain":"test","magnitude":1,"payload":"eyJ0cmFuc2FjdGlvbiI6ICJURVNUIn0="}"#;
+        let req = parse_request(body).unwrap();
+        let expected = blake3::hash(br#"{"transaction": "TEST"}"#);
+        assert_eq!(req.payload_digest(), expected.as_bytes());
+        let body = br#"{"domain":"test","magnitude":1,"payload":""}"#;
+        assert_eq!(parse_request(body).unwrap().payload_digest(), blake3::hash(b"").as_bytes());
+    }
+
+    #[test]
+    fn payload_must_be_canonical_base64_within_limit() {
+        for payload in ["QQ", "QR==", "Q===", "QQ==QQ==", "QQ=A", "Q Q=", "QUJD\\n"] {
+            let body = format!(r#"{{"domain":"test","magnitude":1,"payload":"{}"}}"#, payload);
+            assert!(parse_request(body.as_bytes()).is_none(), "{}", payload);
+        }
+        assert!(parse_request(br#"{"domain":"test","magnitude":1,"payload":7}"#).is_none());
+
+        // The parser's own bound, for hosts that take bodies this large. The
+        // reference runner's 64 KB body cap binds first; its tests fill
+        // the body to that cap.
+        let at_limit = "AAAA".repeat(MAX_PAYLOAD_BYTES / 3) + "AA==";
+        assert_eq!(at_limit.len() / 4 * 3 - 2, MAX_PAYLOAD_BYTES);
+        let body = format!(r#"{{"domain":"test","magnitude":1,"payload":"{}"}}"#, at_limit);
+        assert!(parse_request(body.as_bytes()).is_some());
+        let over = "AAAA".repeat(MAX_PAYLOAD_BYTES / 3 + 1);
+        let body = format!(r#"{{"domain":"test","magnitude":1,"payload":"{}"}}"#, over);
+        assert!(parse_request(body.as_bytes()).is_none());
+    }
+
+    #[test]
+    fn keys_may_have_whitespace_before_the_colon() {
+        let body = b"{\"domain\" :\"test\", \"magnitude\"\t: 1, \"payload\" \n: \"QQ==\"}";
+        let req = parse_request(body).unwrap();
+        assert_eq!(req.parts()[0].domain(), b"test");
+        assert_eq!(req.payload_digest(), blake3::hash(b"A").as_bytes());
+        let body = br#"{"parts" : [{"domain":"test","magnitude":1}]}"#;
+        assert_eq!(parse_request(body).unwrap().parts().len(), 1);
+        // A key's name as a value is not the key.
+        let body = br#"{"client":"payload","domain":"test","magnitude":1,"payload":"QQ=="}"#;
+        assert_eq!(parse_request(body).unwrap().payload_digest(), blake3::hash(b"A").as_bytes());
+    }
+
+    #[test]
+    fn duplicate_keys_are_refused() {
+        for body in [
+            r#"{"domain":"test","magnitude":1,"domain":"deploy"}"#,
+            r#"{"domain":"test","magnitude":1,"magnitude" : 2}"#,
+            r#"{"domain":"test","magnitude":1,"payload":"","payload":"QQ=="}"#,
+            r#"{"parts":[{"domain":"test","magnitude":1}],"parts":[{"domain":"deploy","magnitude":1}]}"#,
+            r#"{"parts":[{"domain":"test","domain":"deploy","magnitude":1}]}"#,
+        ] {
+            assert!(parse_request(body.as_bytes()).is_none(), "{}", body);
+        }
+        // Each part is its own object.
+        let body = br#"{"parts":[{"domain":"test","magnitude":1},{"domain":"deploy","magnitude":1}]}"#;
+        assert_eq!(parse_request(body).unwrap().parts().len(), 2);
+    }
+
+    #[test]
+    fn escapes_are_refused() {
+        let body = br#"{"domain":"test","magnitude":1,"pay\u006coad":"QQ=="}"#;
+        assert!(parse_request(body).is_none());
+        let body = br#"{"domain":"test","magnitude":1,"client":"a\"b"}"#;
+        assert!(parse_request(body).is_none());
+    }
+
+    #[test]
+    fn parse_request_composite_rejects_malformed_part() {
+        let body = br#"{"parts":[{"domain":"deploy","magnitude":1},{"domain":"db_prod"}]}"#;
+        assert!(parse_request(body).is_none());
+    }
+}
@@ -0,0 +1,206 @@
+//! Saturating ledger (opt-in CoreSpec).
+//!
+//! Departs from V1 §3 statelessness by construction: this CoreSpec models the
+//! v0 capacity lifecycle ACTIVE → SATURATED → SEALED across requests.
+//! The phase is never exposed to clients; they still see AUTHORIZED/IMPOSSIBLE.
+//! The host owns the one `Ledger` and serializes access to it.
+
+use crate::corespec::{CORESPEC_SATURATION_THRESHOLD, MAX_COMPOSITE_PARTS};
+use crate::law::{
+    budget_from, budget_remaining, corespec_budget, resolve_law, time_window_open, Budget,
+    LawPart, Magnitude, Refusal, WeekTime,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Phase {
+    Active,
+    Saturated,
+    Sealed,
+}
+
+pub struct Ledger {
+    budget: Budget,
+    phase: Phase,
+}
+
+impl Ledger {
+    pub fn new() -> Self {
+        let budget = corespec_budget();
+        let phase = phase_of(&budget);
+        Self { budget, phase }
+    }
+
+    /// Resolve all parts against the lifetime budget as one unit.
+    /// Parts run against a trial copy of the remaining budget, which
+    /// replaces the ledger only if every part is AUTHORIZED. Once SEALED,
+    /// the law is not consulted again and the budget is never touched.
+    pub fn resolve(
+        &mut self,
+        parts: &[LawPart],
+        now: Option<WeekTime>,
+    ) -> Result<[u32; MAX_COMPOSITE_PARTS], Refusal> {
+        if self.phase == Phase::Sealed {
+            return Err(Refusal::Sealed);
+        }
+        if !parts.iter().all(|p| time_window_open(p.domain, now)) {
+            return Err(Refusal::TimeWindowClosed);
+        }
+        let (capacity, progression) = budget_remaining(&self.budget);
+        let mut trial = budget_from(capacity, progression);
+        let mut applied = [0u32; MAX_COMPOSITE_PARTS];
+        for (slot, part) in applied.iter_mut().zip(parts) {
+            *slot = resolve_law(part.domain, Magnitude(part.magnitude), &mut trial)
+                .ok_or(Refusal::Impossible)?;
+        }
+        self.budget = trial;
+        self.phase = self.phase.max(phase_of(&self.budget));
+        Ok(applied)
+    }
+}
+
+impl Default for Ledger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn phase_of(budget: &Budget) -> Phase {
+    let (capacity, progression) = budget_remaining(budget);
+    if capacity == 0 || progression == 0 {
+        Phase::Sealed
+    } else if capacity < CORESPEC_SATURATION_THRESHOLD {
+        Phase::Saturated
+    } else {
+        Phase::Active
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::corespec::{CORESPEC_CAPACITY, CORESPEC_PROGRESSION};
+    use crate::law::Domain;
+
+    const ANY_TIME: Option<WeekTime> = Some(WeekTime { day: 0, minute: 0 });
+
+    fn remaining(l: &Ledger) -> (u32, u32) {
+        budget_remaining(&l.budget)
+    }
+
+    fn part(magnitude: u32) -> LawPart {
+        LawPart {
+            domain: Domain(0),
+            magnitude,
+        }
+    }
+
+    fn one(l: &mut Ledger, magnitude: u32) -> Option<u32> {
+        l.resolve(&[part(magnitude)], ANY_TIME).ok().map(|applied| applied[0])
+    }
+
+    #[test]
+    fn capacity_is_monotonically_consumed_across_requests() {
+        let mut l = Ledger::new();
+        let mut prev = remaining(&l);
+        let mut prev_phase = l.phase;
+        for m in [1u32, 500, 20_000, 3_000, 7, 4_000, 9_999, 1] {
+            let _ = one(&mut l, m);
+            let now = remaining(&l);
+            assert!(now.0 <= prev.0 && now.1 <= prev.1);
+            assert!(l.phase >= prev_phase);
+            prev = now;
+            prev_phase = l.phase;
+        }
+    }
+
+    #[test]
+    fn ledger_saturates_before_sealing() {
+        let mut l = Ledger::new();
+        assert_eq!(l.phase, Phase::Active);
+        let to_saturate = CORESPEC_CAPACITY - CORESPEC_SATURATION_THRESHOLD + 1;
+        assert!(one(&mut l, to_saturate).is_some());
+        assert_eq!(l.phase, Phase::Saturated);
+        // Still authorizes what fits while saturated.
+        assert!(one(&mut l, 1).is_some());
+        assert_eq!(l.phase, Phase::Saturated);
+    }
+
+    #[test]
+    fn sealed_ledger_is_terminal() {
+        let mut l = Ledger::new();
+        assert!(one(&mut l, CORESPEC_CAPACITY).is_some());
+        assert_eq!(l.phase, Phase::Sealed);
+        let before = remaining(&l);
+        for _ in 0..10 {
+            assert_eq!(l.resolve(&[part(1)], ANY_TIME), Err(Refusal::Sealed));
+        }
+        assert_eq!(remaining(&l), before);
+        assert_eq!(l.phase, Phase::Sealed);
+    }
+
+    #[test]
+    fn composite_failure_consumes_nothing() {
+        let mut l = Ledger::new();
+        let parts = [
+            LawPart {
+                domain: Domain(0),
+                magnitude: 10,
+            },
+            LawPart {
+                domain: Domain(1),
+                magnitude: CORESPEC_CAPACITY,
+            },
+        ];
+        let before = remaining(&l);
+        assert!(l.resolve(&parts, ANY_TIME).is_err());
+        assert_eq!(remaining(&l), before);
+        assert_eq!(l.phase, Phase::Active);
+    }
+
+    #[test]
+    fn composite_success_consumes_every_part() {
+        let mut l = Ledger::new();
+        let parts = [
+            LawPart {
+                domain: Domain(0),
+                magnitude: 10,
+            },
+            LawPart {
+                domain: Domain(1),
+                magnitude: 20,
+            },
+        ];
+        let applied = l.resolve(&parts, ANY_TIME).unwrap();
+        assert_eq!(&applied[..2], &[10, 20]);
+        assert_eq!(
+            remaining(&l),
+            (CORESPEC_CAPACITY - 30, CORESPEC_PROGRESSION - 2)
+        );
+    }
+
+    #[test]
+    fn closed_time_window_consumes_nothing() {
+        let mut l = Ledger::new();
+        let deploy = LawPart {
+            domain: crate::resolve_domain("deploy").unwrap(),
+            magnitude: 10,
+        };
+        // Monday 00:00 UTC: outside every deploy window.
+        let before = remaining(&l);
+        assert_eq!(
+            l.resolve(&[deploy], ANY_TIME),
+            Err(Refusal::TimeWindowClosed)
+        );
+        assert_eq!(remaining(&l), before);
+    }
+
+    #[test]
+    fn exhausted_progression_seals() {
+        let mut l = Ledger::new();
+        for _ in 0..CORESPEC_PROGRESSION {
+            assert!(one(&mut l, 1).is_some());
+        }
+        assert_eq!(l.phase, Phase::Sealed);
+        assert!(one(&mut l, 1).is_none());
+    }
+}
@@ -0,0 +1,202 @@
+//! Signed requests (`corespec_signed_requests`).
+//!
+//! Every request carries an envelope next to its fields:
+//!
+//! ```text
+//! "signature":{"key":"<32-byte public key, hex>","sig":"<64-byte signature, hex>"}
+//! ```
+//!
+//! CBOR bodies carry the same map with byte strings for `key` and `sig`.
+//! Either way the parser keeps the envelope on the `ActionRequest`; an
+//! envelope present but malformed fails the parse.
+//!
+//! `sig` is Ed25519 by `key` over the request's `canonical_bytes`, and
+//! `key` must be listed in `REQUEST_SIGNING_KEYS`. The host checks it
+//! after parse and before `admit`, so no domain is resolved for a request
+//! no approved agent signed. Missing, malformed, unlisted or wrong: all
+//! the same format failure.
+
+use crate::canonical::{canonical_bytes, CANONICAL_MAX_BYTES};
+use crate::corespec::REQUEST_SIGNING_KEYS;
+use crate::parse::{field, ActionRequest};
+use ring::signature::{UnparsedPublicKey, ED25519};
+
+/// True if `req` carries a valid signature over itself by a CoreSpec key.
+pub fn verify_request(req: &ActionRequest) -> bool {
+    verify_with(req, REQUEST_SIGNING_KEYS)
+}
+
+/// As `verify_request`, against `keys` instead of the CoreSpec's list.
+pub fn verify_with(req: &ActionRequest, keys: &[[u8; 32]]) -> bool {
+    let Some((key, sig)) = req.signature else {
+        return false;
+    };
+    if !keys.contains(&key) {
+        return false;
+    }
+    let mut buf = [0u8; CANONICAL_MAX_BYTES];
+    ed25519(&key, canonical_bytes(req, &mut buf), &sig)
+}
+
+fn ed25519(key: &[u8; 32], message: &[u8], sig: &[u8; 64]) -> bool {
+    UnparsedPublicKey::new(&ED25519, key).verify(message, sig).is_ok()
+}
+
+/// `(key, sig)` from the value of the JSON `"signature"` key, if well
+/// formed.
+pub(crate) fn json_envelope(value: &str) -> Option<([u8; 32], [u8; 64])> {
+    let object = value.trim_start().strip_prefix('{')?;
+    let object = &object[..object.find('}')?];
+    Some((
+        hex(string_field(object, "key")?)?,
+        hex(string_field(object, "sig")?)?,
+    ))
+}
+
+fn string_field<'a>(object: &'a str, name: &str) -> Option<&'a str> {
+    let value = field(object, name)??.trim_start().strip_prefix('"')?;
+    Some(&value[..value.find('"')?])
+}
+
+/// Exactly `2 * N` hex digits, either case.
+fn hex<const N: usize>(s: &str) -> Option<[u8; N]> {
+    fn nibble(c: u8) -> Option<u8> {
+        (c as char).to_digit(16).map(|d| d as u8)
+    }
+    if s.len() != 2 * N {
+        return None;
+    }
+    let mut out = [0u8; N];
+    for (byte, pair) in out.iter_mut().zip(s.as_bytes().chunks_exact(2)) {
+        *byte = nibble(pair[0])? << 4 | nibble(pair[1])?;
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parse_request;
+
+    // RFC 8032 §7.1, TEST 1.
+    const RFC_KEY: &str = "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a";
+    const RFC_SIG: &str = "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e06522490155\
+                           5fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b";
+    /// The RFC TEST 1 key over the canonical bytes of
+    /// `{"domain":"test","magnitude":1}`.
+    const REQUEST_SIG: &str = "940e81751835cf25ccaecbac6fd1c83b331f1075650426f998851539ccaeefd1\
+                               61a5bfc5e5016944e78bcbae21ea7234352ddf30996ecce8c86aa4f32a01910b";
+
+    fn key() -> [u8; 32] {
+        hex(RFC_KEY).unwrap()
+    }
+
+    fn signed(fields: &str, sig: &str) -> String {
+        format!(r#"{{{},"signature":{{"key":"{}","sig":"{}"}}}}"#, fields, RFC_KEY, sig)
+    }
+
+    fn verify(body: &str, keys: &[[u8; 32]]) -> bool {
+        let req = parse_request(body.as_bytes()).unwrap();
+        verify_with(&req, keys)
+    }
+
+    fn parses(body: &str) -> bool {
+        parse_request(body.as_bytes()).is_some()
+    }
+
+    #[test]
+    fn rfc8032_test_1() {
+        assert!(ed25519(&key(), b"", &hex(RFC_SIG).unwrap()));
+        assert!(!ed25519(&key(), b"\x00", &hex(RFC_SIG).unwrap()));
+    }
+
+    #[test]
+    fn known_answer_over_canonical_bytes() {
+        let body = signed(r#""domain":"test","magnitude":1"#, REQUEST_SIG);
+        assert!(verify(&body, &[key()]));
+        // Same request, other spelling: same canonical bytes, same signature.
+        let body = format!(
+            r#"{{ "signature" :{{"sig" : "{}", "key":"{}"}}, "magnitude": 1.00, "domain":"test" }}"#,
+            REQUEST_SIG.to_uppercase(),
+            RFC_KEY
+        );
+        assert!(verify(&body, &[key()]));
+    }
+
+    #[test]
+    fn cbor_envelope_verifies_the_same_signature() {
+        use crate::cbor::tests::{bytes, map, text, uint};
+        use crate::parse_request_cbor;
+
+        let envelope = |sig: &[u8]| {
+            map(&[("key", bytes(&key())), ("sig", bytes(sig))])
+        };
+        let body = |sig: &[u8]| {
+            map(&[
+                ("signature", envelope(sig)),
+                ("domain", text("test")),
+                ("magnitude", uint(1)),
+            ])
+        };
+        let sig: [u8; 64] = hex(REQUEST_SIG).unwrap();
+        let req = parse_request_cbor(&body(&sig)).unwrap();
+        assert!(verify_with(&req, &[key()]));
+        assert!(parse_request_cbor(&body(&sig[1..])).is_none());
+    }
+
+    #[test]
+    fn changed_request_fails() {
+        let body = signed(r#""domain":"test","magnitude":2"#, REQUEST_SIG);
+        assert!(!verify(&body, &[key()]));
+        let body = signed(r#""domain":"deploy","magnitude":1"#, REQUEST_SIG);
+        assert!(!verify(&body, &[key()]));
+    }
+
+    #[test]
+    fn appended_payload_fails() {
+        // Signed without a payload: one added later, in any spelling, is
+        // part of the request and breaks the signature.
+        for payload in [r#""payload":"QQ==""#, r#""payload" : "QQ==""#, "\"payload\"\n:\"QQ==\""] {
+            let body = signed(&format!(r#""domain":"test","magnitude":1,{}"#, payload), REQUEST_SIG);
+            assert!(!verify(&body, &[key()]), "{}", payload);
+        }
+        // An empty payload is an omitted one.
+        let body = signed(r#""domain":"test","magnitude":1,"payload" : """#, REQUEST_SIG);
+        assert!(verify(&body, &[key()]));
+        let body = signed(r#""domain":"test","magnitude":1,"pay\u006coad":"QQ==""#, REQUEST_SIG);
+        assert!(!parses(&body));
+    }
+
+    #[test]
+    fn unlisted_key_fails() {
+        let body = signed(r#""domain":"test","magnitude":1"#, REQUEST_SIG);
+        assert!(!verify(&body, &[[0u8; 32]]));
+        assert!(!verify(&body, &[]));
+        let req = parse_request(body.as_bytes()).unwrap();
+        assert!(!verify_request(&req), "reference CoreSpec lists no keys");
+    }
+
+    #[test]
+    fn missing_or_malformed_envelope_fails() {
+        assert!(!verify(r#"{"domain":"test","magnitude":1}"#, &[key()]));
+        let short = &REQUEST_SIG[2..];
+        assert!(!parses(&signed(r#""domain":"test","magnitude":1"#, short)));
+        let not_hex = format!("zz{}", short);
+        assert!(!parses(&signed(r#""domain":"test","magnitude":1"#, &not_hex)));
+        let flat = format!(
+            r#"{{"domain":"test","magnitude":1,"signature":"{}"}}"#,
+            REQUEST_SIG
+        );
+        assert!(!parses(&flat));
+        let no_sig = format!(
+            r#"{{"domain":"test","magnitude":1,"signature":{{"key":"{}"}}}}"#,
+            RFC_KEY
+        );
+        assert!(!parses(&no_sig));
+        let twice = format!(
+            r#"{{"domain":"test","magnitude":1,"signature":{{"key":"{}","sig":"{}","sig":"{}"}}}}"#,
+            RFC_KEY, REQUEST_SIG, REQUEST_SIG
+        );
+        assert!(!parses(&twice));
+    }
+}
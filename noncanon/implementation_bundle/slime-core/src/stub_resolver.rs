@@ -0,0 +1,172 @@
+//! Reference-only action resolver — NOT the real law engine.
+//!
+//! Mirrors the shape of the Anathema-Breaker API so open-source builds
+//! exercise the same lifecycle as `real_ab`:
+//!
+//! ```text
+//! Action<RZ> ──engage──▶ Action<EP> ──commit──▶ Action<IZ> (sealed)
+//! ```
+//!
+//! - `RZ` (reversible zone): the action exists but nothing is consumed.
+//! - `EP` (engagement point): capacity and progression are consumed together,
+//!   atomically. "Engaged but not progressed" cannot be represented.
+//! - `IZ` (irreversible zone): sealed. The only remaining operation is reading
+//!   the effect. `engage` / `commit` are structurally absent.
+//!
+//! Budget inners are private: integrators can construct a budget and read it,
+//! but only the law mutates it (FULL_STACK_CONFORMANCE R-4).
+//! Compile-fail coverage lives in `tests/ui/`.
+
+use core::marker::PhantomData;
+
+#[derive(Clone, Copy)]
+pub struct Domain(pub u16);
+
+#[derive(Clone, Copy)]
+pub struct Magnitude(pub u32);
+
+#[derive(Clone, Copy)]
+pub struct Capacity(pub u32);
+
+#[derive(Clone, Copy)]
+pub struct Progression(pub u32);
+
+pub struct Budget {
+    capacity: Capacity,
+    progression: Progression,
+}
+
+impl Budget {
+    pub fn new(capacity: Capacity, progression: Progression) -> Self {
+        Self {
+            capacity,
+            progression,
+        }
+    }
+
+    pub fn capacity(&self) -> Capacity {
+        self.capacity
+    }
+
+    pub fn progression(&self) -> Progression {
+        self.progression
+    }
+}
+
+// -------------------- Topology --------------------
+
+mod sealed {
+    pub trait Zone {}
+}
+
+/// Reversible zone — action declared, nothing consumed.
+pub struct RZ;
+/// Engagement point — budget consumed, effect not yet sealed.
+pub struct EP;
+/// Irreversible zone — terminal, sealed.
+pub struct IZ;
+
+impl sealed::Zone for RZ {}
+impl sealed::Zone for EP {}
+impl sealed::Zone for IZ {}
+
+/// An action in zone `Z`. Not `Clone`/`Copy`: each transition consumes it.
+pub struct Action<Z: sealed::Zone> {
+    domain: Domain,
+    magnitude: Magnitude,
+    _zone: PhantomData<Z>,
+}
+
+/// Terminal outcome of the law. Carries no reason.
+pub struct Impossibility;
+
+/// Effect produced by a sealed action.
+pub struct Effect {
+    pub domain: Domain,
+    pub magnitude_applied: Magnitude,
+}
+
+impl Action<RZ> {
+    pub fn new(domain: Domain, magnitude: Magnitude) -> Self {
+        Self {
+            domain,
+            magnitude,
+            _zone: PhantomData,
+        }
+    }
+
+    /// Cross the engagement point: consume one progression step and
+    /// `magnitude` capacity, both or neither.
+    pub fn engage(self, budget: &mut Budget) -> Result<Action<EP>, Impossibility> {
+        if budget.progression.0 == 0 || self.magnitude.0 > budget.capacity.0 {
+            return Err(Impossibility);
+        }
+        budget.progression = Progression(budget.progression.0 - 1);
+        budget.capacity = Capacity(budget.capacity.0 - self.magnitude.0);
+        Ok(Action {
+            domain: self.domain,
+            magnitude: self.magnitude,
+            _zone: PhantomData,
+        })
+    }
+}
+
+impl Action<EP> {
+    /// Seal the engaged action. Infallible: the budget was already consumed.
+    pub fn commit(self) -> Action<IZ> {
+        Action {
+            domain: self.domain,
+            magnitude: self.magnitude,
+            _zone: PhantomData,
+        }
+    }
+}
+
+impl Action<IZ> {
+    pub fn effect(&self) -> Effect {
+        Effect {
+            domain: self.domain,
+            magnitude_applied: self.magnitude,
+        }
+    }
+}
+
+/// Stub counterpart of `anathema_breaker_core::pom::resolve_action::resolve_action`.
+pub fn resolve_action(action: Action<RZ>, budget: &mut Budget) -> Result<Effect, Impossibility> {
+    let engaged = action.engage(budget)?;
+    Ok(engaged.commit().effect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn budget(capacity: u32, progression: u32) -> Budget {
+        Budget::new(Capacity(capacity), Progression(progression))
+    }
+
+    #[test]
+    fn engage_consumes_capacity_and_progression() {
+        let mut b = budget(100, 2);
+        let effect = resolve_action(Action::new(Domain(1), Magnitude(40)), &mut b).ok().unwrap();
+        assert_eq!(effect.magnitude_applied.0, 40);
+        assert_eq!(b.capacity().0, 60);
+        assert_eq!(b.progression().0, 1);
+    }
+
+    #[test]
+    fn exhausted_progression_is_impossible() {
+        let mut b = budget(100, 1);
+        assert!(resolve_action(Action::new(Domain(0), Magnitude(1)), &mut b).is_ok());
+        assert!(resolve_action(Action::new(Domain(0), Magnitude(1)), &mut b).is_err());
+        assert_eq!(b.capacity().0, 99);
+    }
+
+    #[test]
+    fn over_capacity_consumes_nothing() {
+        let mut b = budget(10, 1);
+        assert!(resolve_action(Action::new(Domain(0), Magnitude(11)), &mut b).is_err());
+        assert_eq!(b.capacity().0, 10);
+        assert_eq!(b.progression().0, 1);
+    }
+}
@@ -0,0 +1,14 @@
+// Stub law typestate — compile-fail coverage
+//
+// Proves: the stub resolver's RZ → EP → IZ lifecycle cannot be bypassed.
+// Sealed actions expose no transitions, zones cannot be skipped, actions
+// cannot be reused, and budget inners are not writable by integrators.
+//
+// FULL_STACK_CONFORMANCE ref: R-4 (Capacity / Budget / Progression are sealed)
+
+#[cfg(feature = "stub_ab")]
+#[test]
+fn stub_typestate_compile_fail() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
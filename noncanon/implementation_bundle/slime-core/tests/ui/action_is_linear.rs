@@ -0,0 +1,8 @@
+use slime_core::stub_resolver::{Action, Budget, Capacity, Domain, Magnitude, Progression};
+
+fn main() {
+    let mut budget = Budget::new(Capacity(10), Progression(2));
+    let action = Action::new(Domain(0), Magnitude(1));
+    let _first = action.engage(&mut budget);
+    let _second = action.engage(&mut budget);
+}
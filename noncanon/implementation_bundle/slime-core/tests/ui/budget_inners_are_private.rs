@@ -0,0 +1,6 @@
+use slime_core::stub_resolver::{Budget, Capacity, Progression};
+
+fn main() {
+    let mut budget = Budget::new(Capacity(10), Progression(1));
+    budget.capacity = Capacity(u32::MAX);
+}
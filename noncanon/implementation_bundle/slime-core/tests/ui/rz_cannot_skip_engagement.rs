@@ -0,0 +1,6 @@
+use slime_core::stub_resolver::{Action, Domain, Magnitude};
+
+fn main() {
+    let action = Action::new(Domain(0), Magnitude(1));
+    let _ = action.commit();
+}
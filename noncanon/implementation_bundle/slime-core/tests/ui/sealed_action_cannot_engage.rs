@@ -0,0 +1,8 @@
+use slime_core::stub_resolver::{Action, Budget, Capacity, Domain, Magnitude, Progression};
+
+fn main() {
+    let mut budget = Budget::new(Capacity(10), Progression(2));
+    let engaged = Action::new(Domain(0), Magnitude(1)).engage(&mut budget).ok().unwrap();
+    let sealed = engaged.commit();
+    let _ = sealed.engage(&mut budget);
+}
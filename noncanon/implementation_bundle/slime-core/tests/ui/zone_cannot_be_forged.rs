@@ -0,0 +1,9 @@
+use slime_core::stub_resolver::{Action, Domain, Magnitude, IZ};
+
+fn main() {
+    let _forged: Action<IZ> = Action {
+        domain: Domain(0),
+        magnitude: Magnitude(1),
+        _zone: std::marker::PhantomData,
+    };
+}
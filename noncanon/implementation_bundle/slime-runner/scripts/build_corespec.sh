@@ -4,6 +4,12 @@ set -euo pipefail
 # Usage:
 #   scripts/build_corespec.sh enterprise
 #   scripts/build_corespec.sh agent
+#
+# Builds one sealed CoreSpec of the reference runner in release mode and
+# copies it to <target>/release/slime-core-<profile>, then prints that path.
+#   enterprise — the reference CoreSpec: HTTP ingress on 127.0.0.1:8080
+#   agent      — ingress on /run/slime/ingress.sock for local agents
+#                (`corespec_ingress_unix`)
 
 PROFILE="${1:-}"
 if [[ "$PROFILE" != "enterprise" && "$PROFILE" != "agent" ]]; then
@@ -11,12 +17,14 @@ if [[ "$PROFILE" != "enterprise" && "$PROFILE" != "agent" ]]; then
   exit 2
 fi
 
+WORKSPACE="$(cd "$(dirname "$0")/../.." && pwd)"
+
 export CARGO_TERM_COLOR=never
 export RUST_BACKTRACE=0
 export SOURCE_DATE_EPOCH="${SOURCE_DATE_EPOCH:-1700000000}"
 
 # Clear env except minimal allowlist required to run cargo/rustc
-KEEP_VARS=("PATH" "HOME" "USER" "SHELL" "PWD" "RUSTUP_HOME" "CARGO_HOME" "SOURCE_DATE_EPOCH")
+KEEP_VARS=("PATH" "HOME" "USER" "SHELL" "PWD" "RUSTUP_HOME" "CARGO_HOME" "CARGO_TARGET_DIR" "SOURCE_DATE_EPOCH")
 declare -A KEEP
 for v in "${KEEP_VARS[@]}"; do KEEP["$v"]=1; done
 
@@ -29,9 +37,14 @@ export RUSTFLAGS="${RUSTFLAGS:-} -C debuginfo=0 -C strip=symbols"
 
 # CoreSpec feature selection
 if [[ "$PROFILE" == "enterprise" ]]; then
-  FEATURES="--features corespec_enterprise"
+  FEATURES=()
 else
-  FEATURES="--features corespec_agent"
+  FEATURES=(--features corespec_ingress_unix)
 fi
 
-cargo build --release $FEATURES
+cd "$WORKSPACE"
+cargo build --release -p slime-runner --bin slime-runner ${FEATURES[@]+"${FEATURES[@]}"} >&2
+
+TARGET="${CARGO_TARGET_DIR:-$WORKSPACE/target}"
+cp "$TARGET/release/slime-runner" "$TARGET/release/slime-core-$PROFILE"
+echo "$TARGET/release/slime-core-$PROFILE"
@@ -11,6 +11,14 @@ if [[ -z "$BIN" ]]; then
   echo "Usage: $0 <path-to-binary> [out-log]" >&2
   exit 2
 fi
+if [[ ! -x "$BIN" ]]; then
+  echo "Binary not found: $BIN" >&2
+  exit 2
+fi
+if ! command -v strace >/dev/null 2>&1; then
+  echo "strace not found" >&2
+  exit 2
+fi
 
 # Capture file/process/network syscalls during boot sequence
 # Binary may exit(1) quickly if egress is absent — that is expected
@@ -0,0 +1,290 @@
+//! Accept-loop error policy, shared by ingress and the dashboard.
+//!
+//! `accept(2)` errors fall in three classes:
+//!
+//! - per-connection (`ECONNABORTED`, `EPROTO`, `EPERM`, network errors the
+//!   kernel passes through, `EINTR`): that one connection is gone; retry at
+//!   once. `EAGAIN` (nothing queued) blocks in poll(2) on the listener
+//!   for up to `IDLE_WAIT`: a connection is taken as soon as it arrives,
+//!   and the caller still checks its shutdown flag that often.
+//! - exhaustion (`EMFILE`, `ENFILE`, `ENOBUFS`, `ENOMEM`): the queued
+//!   connection stays queued and `accept` would fail again immediately.
+//!   The wait doubles from `MIN_BACKOFF` up to `MAX_BACKOFF`, so the loop
+//!   never spins. On `EMFILE`/`ENFILE` the acceptor also gives up its
+//!   spare fd (a dup of the listener, taken at boot) for one `accept` and
+//!   closes what it gets: the client sees the connection close unanswered,
+//!   as for any connection nothing was decided for, and the backlog drains.
+//!   `accept_exhausted` is logged once per episode.
+//! - anything else (`EBADF`, `EINVAL`, `ENOTSOCK`, …): the listener itself
+//!   is broken. Returned to the caller, which exits (ingress) or stops
+//!   serving (dashboard).
+//!
+//! Waits stay below the watchdog period. poll(2) and
+//! `fcntl(F_DUPFD_CLOEXEC)`, which re-takes the spare, are both in the
+//! syscall sandbox's allowlist.
+
+use crate::log;
+use std::io::{self, ErrorKind};
+use std::net::TcpListener;
+use std::os::fd::{AsFd, AsRawFd, BorrowedFd, OwnedFd};
+use std::os::unix::net::UnixListener;
+use std::time::Duration;
+
+/// Longest wait in poll(2) for a connection when nothing is queued.
+pub const IDLE_WAIT: Duration = Duration::from_millis(250);
+/// First wait between attempts while fds or memory are exhausted.
+pub const MIN_BACKOFF: Duration = Duration::from_millis(25);
+/// Longest wait between attempts while fds or memory are exhausted.
+pub const MAX_BACKOFF: Duration = Duration::from_secs(1);
+
+/// A listening socket: TCP, or Unix for the local ingress.
+pub trait Listen: AsFd {
+    type Stream;
+    fn accept_one(&self) -> io::Result<Self::Stream>;
+}
+
+impl Listen for TcpListener {
+    type Stream = std::net::TcpStream;
+    fn accept_one(&self) -> io::Result<Self::Stream> {
+        self.accept().map(|(stream, _)| stream)
+    }
+}
+
+impl Listen for UnixListener {
+    type Stream = std::os::unix::net::UnixStream;
+    fn accept_one(&self) -> io::Result<Self::Stream> {
+        self.accept().map(|(stream, _)| stream)
+    }
+}
+
+pub struct Acceptor<L: Listen> {
+    listener: L,
+    /// Released to accept-and-close one connection under `EMFILE`.
+    spare: Option<OwnedFd>,
+    backoff: Duration,
+    exhausted: bool,
+}
+
+/// Block until `fd` has a connection queued, a signal arrives or
+/// `timeout` passes. A failed poll is not reported: the `accept` that
+/// follows meets the same error.
+fn wait_readable(fd: BorrowedFd<'_>, timeout: Duration) {
+    let mut pfd = libc::pollfd {
+        fd: fd.as_raw_fd(),
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    // SAFETY: one valid `pollfd` for the duration of the call.
+    unsafe { libc::poll(&mut pfd, 1, timeout.as_millis() as libc::c_int) };
+}
+
+enum Class {
+    Idle,
+    Connection,
+    Exhausted,
+    Fatal,
+}
+
+fn classify(e: &io::Error) -> Class {
+    if e.kind() == ErrorKind::WouldBlock {
+        return Class::Idle;
+    }
+    match e.raw_os_error() {
+        Some(
+            libc::ECONNABORTED
+            | libc::EINTR
+            | libc::EPROTO
+            | libc::EPERM
+            | libc::ENETDOWN
+            | libc::ENETUNREACH
+            | libc::ENOPROTOOPT
+            | libc::EHOSTDOWN
+            | libc::EHOSTUNREACH
+            | libc::ENONET
+            | libc::EOPNOTSUPP
+            | libc::ETIMEDOUT,
+        ) => Class::Connection,
+        Some(libc::EMFILE | libc::ENFILE | libc::ENOBUFS | libc::ENOMEM) => Class::Exhausted,
+        _ => Class::Fatal,
+    }
+}
+
+impl<L: Listen> Acceptor<L> {
+    pub fn new(listener: L) -> Self {
+        let spare = listener.as_fd().try_clone_to_owned().ok();
+        Self {
+            listener,
+            spare,
+            backoff: MIN_BACKOFF,
+            exhausted: false,
+        }
+    }
+
+    /// One attempt. `Ok(None)` after waiting as the error class requires;
+    /// `Err` only for a broken listener.
+    pub fn next(&mut self) -> io::Result<Option<L::Stream>> {
+        let e = match self.listener.accept_one() {
+            Ok(stream) => {
+                self.backoff = MIN_BACKOFF;
+                self.exhausted = false;
+                self.reserve();
+                return Ok(Some(stream));
+            }
+            Err(e) => e,
+        };
+        match classify(&e) {
+            Class::Idle => wait_readable(self.listener.as_fd(), IDLE_WAIT),
+            Class::Connection => {}
+            Class::Exhausted => {
+                if !self.exhausted {
+                    self.exhausted = true;
+                    log::emit(log::Event::AcceptExhausted, e.raw_os_error());
+                }
+                if matches!(e.raw_os_error(), Some(libc::EMFILE | libc::ENFILE)) {
+                    self.shed();
+                }
+                std::thread::sleep(self.backoff);
+                self.backoff = (self.backoff * 2).min(MAX_BACKOFF);
+            }
+            Class::Fatal => return Err(e),
+        }
+        Ok(None)
+    }
+
+    /// Spend the spare fd on one queued connection and close it unanswered.
+    fn shed(&mut self) {
+        if self.spare.take().is_some() {
+            drop(self.listener.accept_one());
+        }
+        self.reserve();
+    }
+
+    fn reserve(&mut self) {
+        if self.spare.is_none() {
+            self.spare = self.listener.as_fd().try_clone_to_owned().ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::failstop::{child_point, reexec, Point};
+    use std::io::Read;
+    use std::net::TcpStream;
+    use std::time::Instant;
+
+    /// Open fds until the soft limit is hit.
+    fn exhaust(fill_from: &TcpListener) -> Vec<OwnedFd> {
+        let mut fillers = Vec::new();
+        loop {
+            match fill_from.as_fd().try_clone_to_owned() {
+                Ok(fd) => fillers.push(fd),
+                Err(e) => {
+                    assert_eq!(e.raw_os_error(), Some(libc::EMFILE));
+                    return fillers;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn exhausted_fds_back_off_shed_and_recover() {
+        if child_point().is_none() {
+            let out = reexec(
+                "accept::tests::exhausted_fds_back_off_shed_and_recover",
+                Point::Child,
+                &[],
+            );
+            let stderr = String::from_utf8_lossy(&out.stderr);
+            assert!(out.status.success(), "{}", stderr);
+            // Logged once for the whole episode.
+            assert_eq!(stderr.matches("\"event\":\"accept_exhausted\"").count(), 1, "{}", stderr);
+            return;
+        }
+
+        // Alone in this process: the fd limit is process-wide.
+        let limit = libc::rlimit {
+            rlim_cur: 64,
+            rlim_max: 64,
+        };
+        // SAFETY: the pointer is valid for the call.
+        assert_eq!(unsafe { libc::setrlimit(libc::RLIMIT_NOFILE, &limit) }, 0);
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.set_nonblocking(true).unwrap();
+        let addr = listener.local_addr().unwrap();
+        let mut acceptor = Acceptor::new(listener.try_clone().unwrap());
+        let mut queued = TcpStream::connect(addr).unwrap();
+        let fillers = exhaust(&listener);
+
+        // Exhausted: the queued connection is shed, and the loop waits.
+        let start = Instant::now();
+        let mut attempts = 0;
+        while start.elapsed() < Duration::from_millis(500) {
+            assert!(acceptor.next().unwrap().is_none());
+            attempts += 1;
+        }
+        // 25 + 50 + 100 + 200 + 400 ms: a handful of attempts, not a spin.
+        assert!(attempts <= 6, "{} attempts in 500 ms", attempts);
+        let mut buf = [0u8; 1];
+        assert_eq!(queued.read(&mut buf).unwrap(), 0, "shed connection closes unanswered");
+
+        // Recovered: fds freed, the next connection is served.
+        drop(fillers);
+        let _client = TcpStream::connect(addr).unwrap();
+        let start = Instant::now();
+        let served = loop {
+            if let Some(stream) = acceptor.next().unwrap() {
+                break stream;
+            }
+            assert!(start.elapsed() < 2 * MAX_BACKOFF, "no recovery");
+        };
+        drop(served);
+        assert!(acceptor.spare.is_some());
+        assert_eq!(acceptor.backoff, MIN_BACKOFF);
+    }
+
+    #[test]
+    fn idle_wait_wakes_for_a_connection() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.set_nonblocking(true).unwrap();
+        let addr = listener.local_addr().unwrap();
+        let mut acceptor = Acceptor::new(listener);
+
+        // Nothing queued: one attempt waits out `IDLE_WAIT`.
+        let start = Instant::now();
+        assert!(acceptor.next().unwrap().is_none());
+        assert!(start.elapsed() >= IDLE_WAIT - Duration::from_millis(10));
+
+        // A connection arriving mid-wait ends it.
+        let client = std::thread::spawn(move || {
+            std::thread::sleep(IDLE_WAIT / 5);
+            TcpStream::connect(addr).unwrap()
+        });
+        let start = Instant::now();
+        let served = loop {
+            if let Some(stream) = acceptor.next().unwrap() {
+                break stream;
+            }
+        };
+        assert!(start.elapsed() < IDLE_WAIT, "waited {:?}", start.elapsed());
+        drop((served, client.join().unwrap()));
+    }
+
+    #[test]
+    fn broken_listener_is_fatal() {
+        assert!(matches!(
+            classify(&io::Error::from_raw_os_error(libc::EBADF)),
+            Class::Fatal
+        ));
+        assert!(matches!(
+            classify(&io::Error::from_raw_os_error(libc::ECONNABORTED)),
+            Class::Connection
+        ));
+        assert!(matches!(
+            classify(&io::Error::from_raw_os_error(libc::EAGAIN)),
+            Class::Idle
+        ));
+    }
+}
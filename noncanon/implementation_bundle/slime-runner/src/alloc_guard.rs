@@ -0,0 +1,71 @@
+//! Test-mode global allocator: proves the request path never allocates.
+//!
+//! `arm()` turns the guard on for every thread in the process, as boot
+//! completing would; `disarm()` turns it off and returns how many
+//! allocations were made in between. The allocator still serves them —
+//! unwinding out of `GlobalAlloc` is undefined behavior — so the caller
+//! asserts the count. Arming is process-wide, so a test that needs a zero
+//! count runs alone in a re-executed test process (`failstop::reexec`).
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+struct Guard;
+
+static ARMED: AtomicBool = AtomicBool::new(false);
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+fn note() {
+    if ARMED.load(Ordering::SeqCst) {
+        ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+// SAFETY: every call is forwarded unchanged to `System`.
+unsafe impl GlobalAlloc for Guard {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        note();
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        note();
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        note();
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Guard = Guard;
+
+/// Count every allocation in the process from now on.
+pub fn arm() {
+    ALLOCATIONS.store(0, Ordering::SeqCst);
+    ARMED.store(true, Ordering::SeqCst);
+}
+
+/// Stop counting; the allocations made since `arm`.
+pub fn disarm() -> usize {
+    ARMED.store(false, Ordering::SeqCst);
+    ALLOCATIONS.load(Ordering::SeqCst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allocation_while_armed_is_counted() {
+        arm();
+        std::hint::black_box(Vec::<u8>::with_capacity(16));
+        assert!(disarm() >= 1);
+    }
+}
@@ -0,0 +1,433 @@
+//! Operator audit trail — decision records, never client-visible.
+//!
+//! FULL_STACK_CONFORMANCE R-1: internal reasons are allowed in operator
+//! audit only. Records go to a fixed append-only file; nothing here is
+//! reachable from the HTTP response path. Each decision is recorded before
+//! its egress frames; a record that cannot be written stops the runner
+//! (`audit_write_failed`, exit 1) before any frame or reply, so the trail
+//! never falls behind the decisions it covers.
+//!
+//! One JSON object per line, hash-chained (see `slime_audit_chain`):
+//!
+//! ```text
+//! {"seq":7,"kind":"decision","ts_ms":..,"stage":"law","domain_id":2,"magnitude":5,"verdict":"IMPOSSIBLE","reason":"time_window_closed","prev":"…"}
+//! ```
+//!
+//! `domain_id` / `magnitude` are `null` when the request died before they
+//! were known. `magnitude` is the value in the domain's unit as the law
+//! saw it; a magnitude-stage refusal has no such value and records the
+//! client's decimal as written, as a string (`"0.000"`). Each boot appends a `boot` record to the existing chain;
+//! `checkpoint` records are interleaved at a fixed interval.
+
+use crate::log;
+use slime_core::{DecimalMagnitude, Refusal};
+use slime_audit_chain::{self as audit_chain, Chain};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::os::unix::fs::{FileExt, OpenOptionsExt};
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const LOG_PATH: &str = "/var/log/slime/audit.log";
+const MAX_RECORD_BYTES: usize = 256;
+
+struct Sink {
+    file: File,
+    chain: Chain,
+    /// File length after the last sealed line.
+    len: u64,
+}
+
+static SINK: OnceLock<Mutex<Sink>> = OnceLock::new();
+
+/// Where in `ingress::handle` the decision was made.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Stage {
+    Peer,
+    Header,
+    Parse,
+    Domain,
+    Magnitude,
+    Law,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Verdict {
+    Authorized,
+    Impossible,
+}
+
+/// Internal reason. Audit-only (R-1).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Reason {
+    PeerCredentials,
+    HeaderRead,
+    Malformed,
+    #[cfg(feature = "corespec_signed_requests")]
+    SignatureInvalid,
+    UnknownDomain,
+    DuplicateDomain,
+    MagnitudeRange,
+    TimeWindowClosed,
+    LawImpossible,
+    #[cfg(feature = "corespec_saturating")]
+    Sealed,
+    Authorized,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Magnitude {
+    /// In the domain's unit.
+    Scaled(u64),
+    /// As the client wrote it, before scaling.
+    Written(DecimalMagnitude),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Record {
+    pub stage: Stage,
+    pub domain_id: Option<u64>,
+    pub magnitude: Option<Magnitude>,
+    pub verdict: Verdict,
+    pub reason: Reason,
+}
+
+impl Stage {
+    /// Every stage, in `ingress::handle` order.
+    pub const ALL: [Stage; 6] = [
+        Stage::Peer,
+        Stage::Header,
+        Stage::Parse,
+        Stage::Domain,
+        Stage::Magnitude,
+        Stage::Law,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Stage::Peer => "peer",
+            Stage::Header => "header",
+            Stage::Parse => "parse",
+            Stage::Domain => "domain",
+            Stage::Magnitude => "magnitude",
+            Stage::Law => "law",
+        }
+    }
+}
+
+impl Verdict {
+    fn as_str(self) -> &'static str {
+        match self {
+            Verdict::Authorized => "AUTHORIZED",
+            Verdict::Impossible => "IMPOSSIBLE",
+        }
+    }
+}
+
+impl Reason {
+    fn as_str(self) -> &'static str {
+        match self {
+            Reason::PeerCredentials => "peer_not_admitted",
+            Reason::HeaderRead => "header_read_failed",
+            Reason::Malformed => "request_malformed",
+            #[cfg(feature = "corespec_signed_requests")]
+            Reason::SignatureInvalid => "signature_invalid",
+            Reason::UnknownDomain => "unknown_domain",
+            Reason::DuplicateDomain => "duplicate_domain",
+            Reason::MagnitudeRange => "magnitude_out_of_range",
+            Reason::TimeWindowClosed => "time_window_closed",
+            Reason::LawImpossible => "law_impossible",
+            #[cfg(feature = "corespec_saturating")]
+            Reason::Sealed => "ledger_sealed",
+            Reason::Authorized => "authorized",
+        }
+    }
+}
+
+impl From<Refusal> for Reason {
+    fn from(refusal: Refusal) -> Self {
+        match refusal {
+            Refusal::TimeWindowClosed => Reason::TimeWindowClosed,
+            Refusal::Impossible => Reason::LawImpossible,
+            #[cfg(feature = "corespec_saturating")]
+            Refusal::Sealed => Reason::Sealed,
+        }
+    }
+}
+
+impl Record {
+    /// Record for a request that ended before reaching the law.
+    pub fn rejected(stage: Stage, reason: Reason) -> Self {
+        Self {
+            stage,
+            domain_id: None,
+            magnitude: None,
+            verdict: Verdict::Impossible,
+            reason,
+        }
+    }
+}
+
+/// Open the audit sink and chain a `boot` record onto any existing log.
+/// Exits before ingress bind if the log cannot be opened, or if its last
+/// line is not a sealed record (V1 §7.2: no partial initialization).
+/// Inspect a refused log with `slime-audit verify`.
+pub fn init_fail_closed() {
+    open_fail_closed(Path::new(LOG_PATH));
+}
+
+/// The sink on a log the test owns, opened as at boot.
+#[cfg(test)]
+pub fn init_for_test(path: &Path) {
+    open_fail_closed(path);
+}
+
+fn open_fail_closed(path: &Path) {
+    let file = OpenOptions::new()
+        .read(true)
+        .create(true)
+        .append(true)
+        .mode(0o640)
+        .open(path)
+        .unwrap_or_else(|e| log::fatal(log::Event::AuditOpenFailed, e.raw_os_error()));
+    let chain = resume_chain(&file)
+        .unwrap_or_else(|| log::fatal(log::Event::AuditChainUnreadable, None));
+    let len = file
+        .metadata()
+        .unwrap_or_else(|e| log::fatal(log::Event::AuditOpenFailed, e.raw_os_error()))
+        .len();
+    let mut sink = Sink { file, chain, len };
+    let mut fields = [0u8; MAX_RECORD_BYTES];
+    let mut w = &mut fields[..];
+    let _ = write!(w, "\"kind\":\"boot\",\"ts_ms\":{}", now_ms());
+    let len = MAX_RECORD_BYTES - w.len();
+    if !sink.append(&fields[..len]) {
+        log::fatal(log::Event::AuditWriteFailed, None);
+    }
+    let _ = SINK.set(Mutex::new(sink));
+}
+
+/// Chain state after the last line of `file`.
+fn resume_chain(file: &File) -> Option<Chain> {
+    let len = file.metadata().ok()?.len();
+    if len == 0 {
+        return Some(Chain::genesis());
+    }
+    let mut tail = [0u8; audit_chain::MAX_LINE_BYTES + 1];
+    let start = len.saturating_sub(tail.len() as u64);
+    let n = (len - start) as usize;
+    file.read_exact_at(&mut tail[..n], start).ok()?;
+    let body = tail[..n].strip_suffix(b"\n")?;
+    let last = match body.iter().rposition(|&b| b == b'\n') {
+        Some(p) => &body[p + 1..],
+        None if start == 0 => body,
+        None => return None,
+    };
+    Chain::resume(last)
+}
+
+impl Sink {
+    /// Seal and write one line, then a checkpoint if one is due. The chain
+    /// advances only past a line wholly on disk; a failed write is cut
+    /// back off the file, which still ends on a sealed line.
+    fn append(&mut self, fields: &[u8]) -> bool {
+        let mut line = [0u8; audit_chain::MAX_LINE_BYTES];
+        let mut next = self.chain;
+        let Some(len) = next.seal(fields, &mut line) else {
+            return false;
+        };
+        if self.file.write_all(&line[..len]).is_err() {
+            let _ = self.file.set_len(self.len);
+            return false;
+        }
+        self.chain = next;
+        self.len += len as u64;
+
+        if !self.chain.checkpoint_due() {
+            return true;
+        }
+        let mut cp = [0u8; MAX_RECORD_BYTES];
+        let mut w = &mut cp[..];
+        let _ = write!(w, "\"kind\":\"checkpoint\",\"ts_ms\":{}", now_ms());
+        let cp_len = MAX_RECORD_BYTES - w.len();
+        if !self.append(&cp[..cp_len]) {
+            return false;
+        }
+        // Out of band: a cut tail cannot take this with it.
+        if let Some(anchor) = self.chain.last() {
+            log::emit(log::Event::AuditCheckpoint(anchor), None);
+        }
+        true
+    }
+}
+
+/// Append one record. A record that does not encode or a failed write,
+/// like a poisoned sink (`failstop`), is fatal.
+pub fn record(rec: &Record) {
+    let Some(sink) = SINK.get() else {
+        return;
+    };
+    let mut buf = [0u8; MAX_RECORD_BYTES];
+    let Some(len) = encode(rec, now_ms(), &mut buf) else {
+        log::fatal(log::Event::AuditWriteFailed, None);
+    };
+    if !crate::failstop::lock(sink).append(&buf[..len]) {
+        log::fatal(log::Event::AuditWriteFailed, None);
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Encode the record's members (no braces, no chain fields) into `buf`
+/// without allocating.
+fn encode(rec: &Record, ts_ms: u64, buf: &mut [u8]) -> Option<usize> {
+    let capacity = buf.len();
+    let mut w = &mut buf[..];
+    write!(
+        w,
+        "\"kind\":\"decision\",\"ts_ms\":{},\"stage\":\"{}\",",
+        ts_ms,
+        rec.stage.as_str()
+    )
+    .ok()?;
+    match rec.domain_id {
+        Some(id) => write!(w, "\"domain_id\":{},", id).ok()?,
+        None => write!(w, "\"domain_id\":null,").ok()?,
+    }
+    match rec.magnitude {
+        Some(Magnitude::Scaled(m)) => write!(w, "\"magnitude\":{},", m).ok()?,
+        Some(Magnitude::Written(DecimalMagnitude {
+            int,
+            frac_digits: 0,
+            ..
+        })) => write!(w, "\"magnitude\":\"{}\",", int).ok()?,
+        Some(Magnitude::Written(m)) => write!(
+            w,
+            "\"magnitude\":\"{}.{:0width$}\",",
+            m.int,
+            m.frac,
+            width = m.frac_digits as usize
+        )
+        .ok()?,
+        None => write!(w, "\"magnitude\":null,").ok()?,
+    }
+    write!(
+        w,
+        "\"verdict\":\"{}\",\"reason\":\"{}\"",
+        rec.verdict.as_str(),
+        rec.reason.as_str()
+    )
+    .ok()?;
+    Some(capacity - w.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_known_answer() {
+        let rec = Record {
+            stage: Stage::Law,
+            domain_id: Some(2),
+            magnitude: Some(Magnitude::Scaled(5)),
+            verdict: Verdict::Impossible,
+            reason: Reason::TimeWindowClosed,
+        };
+        let mut buf = [0u8; MAX_RECORD_BYTES];
+        let len = encode(&rec, 1_700_000_000_000, &mut buf).unwrap();
+        assert_eq!(
+            std::str::from_utf8(&buf[..len]).unwrap(),
+            "\"kind\":\"decision\",\"ts_ms\":1700000000000,\"stage\":\"law\",\"domain_id\":2,\
+             \"magnitude\":5,\"verdict\":\"IMPOSSIBLE\",\"reason\":\"time_window_closed\""
+        );
+    }
+
+    #[test]
+    fn encode_nulls_unknown_fields() {
+        let rec = Record::rejected(Stage::Header, Reason::HeaderRead);
+        let mut buf = [0u8; MAX_RECORD_BYTES];
+        let len = encode(&rec, 0, &mut buf).unwrap();
+        let line = std::str::from_utf8(&buf[..len]).unwrap();
+        assert!(line.contains("\"domain_id\":null,\"magnitude\":null,"));
+        assert!(line.ends_with("\"reason\":\"header_read_failed\""));
+    }
+
+    #[test]
+    fn encode_keeps_the_written_fraction() {
+        for (written, expected) in [
+            ("0.000", "\"magnitude\":\"0.000\","),
+            ("42949673.05", "\"magnitude\":\"42949673.05\","),
+            ("0", "\"magnitude\":\"0\","),
+        ] {
+            let rec = Record {
+                domain_id: Some(1),
+                magnitude: Some(Magnitude::Written(
+                    slime_core::parse_decimal(written).unwrap(),
+                )),
+                ..Record::rejected(Stage::Magnitude, Reason::MagnitudeRange)
+            };
+            let mut buf = [0u8; MAX_RECORD_BYTES];
+            let len = encode(&rec, 0, &mut buf).unwrap();
+            let line = std::str::from_utf8(&buf[..len]).unwrap();
+            assert!(line.contains(expected), "{}", line);
+        }
+    }
+
+    fn temp_log(name: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("slime-sink-{}-{}.log", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        File::create(&path).unwrap();
+        path
+    }
+
+    #[test]
+    fn failed_write_leaves_the_chain_where_the_file_is() {
+        let path = temp_log("readonly");
+        let mut sink = Sink {
+            file: File::open(&path).unwrap(),
+            chain: Chain::genesis(),
+            len: 0,
+        };
+        assert!(!sink.append(b"\"kind\":\"boot\""));
+        assert_eq!(sink.chain.last(), None);
+
+        sink.file = OpenOptions::new().append(true).open(&path).unwrap();
+        for _ in 0..audit_chain::CHECKPOINT_INTERVAL + 3 {
+            assert!(sink.append(b"\"kind\":\"boot\""));
+        }
+        let log = std::fs::read(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(sink.len, log.len() as u64);
+        let summary = audit_chain::verify(&log).unwrap();
+        assert_eq!((summary.records, summary.checkpoints), (68, 1));
+        assert_eq!(sink.chain.last().unwrap().hash, summary.head);
+    }
+
+    #[test]
+    fn worst_case_record_fits_buffer() {
+        let widest = "18446744073709551615.999999999999999999";
+        let rec = Record {
+            stage: Stage::Magnitude,
+            domain_id: Some(u64::MAX),
+            magnitude: Some(Magnitude::Written(
+                slime_core::parse_decimal(widest).unwrap(),
+            )),
+            verdict: Verdict::Impossible,
+            reason: Reason::MagnitudeRange,
+        };
+        let mut buf = [0u8; MAX_RECORD_BYTES];
+        let len = encode(&rec, u64::MAX, &mut buf).unwrap();
+        // Widest seq the chain can still seal, plus a full record.
+        let last = format!("{{\"seq\":{},\"prev\":\"{}\"}}", u64::MAX - 2, "0".repeat(64));
+        let mut chain = Chain::resume(last.as_bytes()).unwrap();
+        let mut line = [0u8; audit_chain::MAX_LINE_BYTES];
+        assert!(chain.seal(&buf[..len], &mut line).is_some());
+    }
+}
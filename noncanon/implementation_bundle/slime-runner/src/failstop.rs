@@ -0,0 +1,167 @@
+//! Fail-stop: a panic anywhere ends the process (V1 §7.2).
+//!
+//! The hook installed by `install` runs before any unwinding (and before
+//! the abort of `panic = "abort"` release builds): it emits `panic` and
+//! `exit`, then terminates with code 1. No destructor runs and no other
+//! thread gets to serve another request. A half-handled request leaves
+//! nothing behind: its frames go out in one write in `egress::apply` and
+//! its reply in one write at the end of `ingress::handle`, so a panic
+//! before either means that one is never sent — the client sees the
+//! connection close empty.
+//!
+//! The panic message is not logged: it can carry request bytes.
+//!
+//! Since nothing survives a panic, no lock is ever found poisoned. `lock`
+//! still treats poisoning as fatal instead of unwrapping, so the policy
+//! does not hinge on the hook being installed.
+
+use crate::log;
+use std::sync::{Mutex, MutexGuard};
+
+/// Install the panic hook. First thing in `main`.
+pub fn install() {
+    std::panic::set_hook(Box::new(|_| log::fatal(log::Event::Panic, None)));
+}
+
+/// Lock `m`; exit if a panicking thread poisoned it.
+pub fn lock<T>(m: &Mutex<T>) -> MutexGuard<'_, T> {
+    m.lock().unwrap_or_else(|_| log::fatal(log::Event::LockPoisoned, None))
+}
+
+//
+// -------------------- Fault injection (tests only) --------------------
+//
+// A panic must end the real process, so these tests re-execute the test
+// binary for one test with `CHILD_ENV` set. The child arms a `Point`, and
+// `fault` panics when the request path reaches it.
+//
+
+#[cfg(test)]
+pub use self::inject::*;
+
+#[cfg(test)]
+mod inject {
+    use std::os::fd::RawFd;
+    use std::os::unix::process::CommandExt;
+    use std::process::{Command, Output};
+    use std::sync::atomic::{AtomicU8, Ordering};
+
+    /// Where the request path panics when armed.
+    #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+    pub enum Point {
+        /// In the test child, with nothing armed.
+        Child,
+        /// After the law decided, before anything is written.
+        Decision,
+        /// Frames encoded, not yet written to egress.
+        Egress,
+        /// Frames written, reply not yet written.
+        Reply,
+    }
+
+    const CHILD_ENV: &str = "SLIME_FAILSTOP_CHILD";
+    /// Where the child finds the fds handed to `reexec`, in order.
+    pub const INHERITED_FDS: [RawFd; 2] = [200, 201];
+
+    static ARMED: AtomicU8 = AtomicU8::new(Point::Child as u8);
+
+    pub fn arm(point: Point) {
+        ARMED.store(point as u8, Ordering::SeqCst);
+    }
+
+    pub fn fault(point: Point) {
+        if point != Point::Child && ARMED.load(Ordering::SeqCst) == point as u8 {
+            panic!("injected at {:?}", point);
+        }
+    }
+
+    /// `Some` inside a child started by `reexec`: the point to arm.
+    pub fn child_point() -> Option<Point> {
+        let v = std::env::var(CHILD_ENV).ok()?;
+        [Point::Child, Point::Decision, Point::Egress, Point::Reply]
+            .into_iter()
+            .find(|p| (*p as u8).to_string() == v)
+    }
+
+    /// Run test `name` alone in a fresh test process, with `fds` at
+    /// `INHERITED_FDS`.
+    pub fn reexec(name: &str, point: Point, fds: &[RawFd]) -> Output {
+        let fds: Vec<RawFd> = fds.to_vec();
+        assert!(fds.len() <= INHERITED_FDS.len());
+        assert!(fds.iter().all(|fd| !INHERITED_FDS.contains(fd)));
+        let mut cmd = Command::new(std::env::current_exe().unwrap());
+        cmd.args([name, "--exact", "--nocapture", "--test-threads=1"])
+            .env(CHILD_ENV, (point as u8).to_string());
+        // SAFETY: only dup2 between fork and exec; the copies drop CLOEXEC.
+        unsafe {
+            cmd.pre_exec(move || {
+                for (&fd, &to) in fds.iter().zip(&INHERITED_FDS) {
+                    if libc::dup2(fd, to) < 0 {
+                        return Err(std::io::Error::last_os_error());
+                    }
+                }
+                Ok(())
+            });
+        }
+        cmd.output().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stderr_lines(out: &std::process::Output) -> Vec<String> {
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        stderr
+            .lines()
+            .filter(|l| l.starts_with("{\"ts\""))
+            .map(str::to_owned)
+            .collect()
+    }
+
+    #[test]
+    fn panic_logs_without_its_message_and_exits_1() {
+        if child_point().is_some() {
+            install();
+            panic!("request bytes {{\"domain\":\"secret\"}}");
+        }
+        let out = reexec(
+            "failstop::tests::panic_logs_without_its_message_and_exits_1",
+            Point::Child,
+            &[],
+        );
+        assert_eq!(out.status.code(), Some(1));
+        let lines = stderr_lines(&out);
+        assert_eq!(lines.len(), 2, "{:?}", lines);
+        assert!(lines[0].ends_with("\"level\":\"ERROR\",\"event\":\"panic\"}"));
+        assert!(lines[1].ends_with("\"event\":\"exit\",\"code\":1}"));
+        assert!(!String::from_utf8_lossy(&out.stderr).contains("secret"));
+    }
+
+    #[test]
+    fn poisoned_lock_is_fatal_without_the_hook() {
+        if child_point().is_some() {
+            let m = Mutex::new(0u8);
+            let _ = std::thread::scope(|s| {
+                s.spawn(|| {
+                    let _guard = m.lock();
+                    panic!("poison");
+                })
+                .join()
+            });
+            let _guard = lock(&m);
+            return;
+        }
+        let out = reexec(
+            "failstop::tests::poisoned_lock_is_fatal_without_the_hook",
+            Point::Child,
+            &[],
+        );
+        assert_eq!(out.status.code(), Some(1));
+        let lines = stderr_lines(&out);
+        assert_eq!(lines.len(), 2, "{:?}", lines);
+        assert!(lines[0].ends_with("\"event\":\"lock_poisoned\"}"));
+        assert!(lines[1].ends_with("\"event\":\"exit\",\"code\":1}"));
+    }
+}
@@ -0,0 +1,175 @@
+//! Unix-socket ingress for local agents (`corespec_ingress_unix`).
+//!
+//! The same HTTP ingress, on a fixed socket instead of `127.0.0.1:8080`:
+//!
+//! - `SOCKET_PATH`, mode `0660`, group from the unit (`RuntimeDirectory=`).
+//!   The file is created by `bind_fail_closed` while Landlock still allows
+//!   it; nothing listens until `ingress::start_local`, so no client
+//!   connects before boot completes (V1 §7.2).
+//! - The kernel records a client's uid and gid at `connect(2)`
+//!   (`SO_PEERCRED`); a client cannot forge them. It is admitted if its uid
+//!   is in `INGRESS_PEER_UIDS` or its gid in `INGRESS_PEER_GIDS`. Anyone
+//!   else gets IMPOSSIBLE before a byte of the request is read.
+
+use crate::log;
+use slime_core::corespec::{INGRESS_PEER_GIDS, INGRESS_PEER_UIDS};
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::net::UnixStream;
+
+pub const SOCKET_PATH: &str = "/run/slime/ingress.sock";
+const SOCKET_MODE: u32 = 0o660;
+
+fn fail(errno: Option<i32>) -> ! {
+    log::fatal(log::Event::IngressBindFailed, errno)
+}
+
+fn last_errno() -> Option<i32> {
+    std::io::Error::last_os_error().raw_os_error()
+}
+
+// Leaves the trailing NUL of `sun_path`.
+const _: () = assert!(SOCKET_PATH.len() < 108);
+
+/// `sockaddr_un` for `path`; a path too long for `sun_path` is cut.
+fn sockaddr(path: &str) -> libc::sockaddr_un {
+    // SAFETY: all-zero is a valid `sockaddr_un`.
+    let mut addr: libc::sockaddr_un = unsafe { std::mem::zeroed() };
+    addr.sun_family = libc::AF_UNIX as libc::sa_family_t;
+    let room = addr.sun_path.len() - 1;
+    for (dst, src) in addr.sun_path[..room].iter_mut().zip(path.bytes()) {
+        *dst = src as libc::c_char;
+    }
+    addr
+}
+
+/// Create and bind the socket file, replacing a stale one. Not listening.
+pub fn bind_fail_closed() -> OwnedFd {
+    let addr = sockaddr(SOCKET_PATH);
+
+    if let Err(e) = std::fs::remove_file(SOCKET_PATH) {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            fail(e.raw_os_error());
+        }
+    }
+    // SAFETY: plain socket call; the fd is owned from here on.
+    let fd = unsafe { libc::socket(libc::AF_UNIX, libc::SOCK_STREAM | libc::SOCK_CLOEXEC, 0) };
+    if fd < 0 {
+        fail(last_errno());
+    }
+    // SAFETY: `fd` is a fresh socket nothing else owns.
+    let socket = unsafe { OwnedFd::from_raw_fd(fd) };
+    let len = std::mem::size_of::<libc::sockaddr_un>() as libc::socklen_t;
+    // SAFETY: `addr` is a valid `sockaddr_un` of `len` bytes.
+    let rc = unsafe { libc::bind(socket.as_raw_fd(), &addr as *const _ as *const _, len) };
+    if rc != 0 {
+        fail(last_errno());
+    }
+    let mode = std::fs::Permissions::from_mode(SOCKET_MODE);
+    if let Err(e) = std::fs::set_permissions(SOCKET_PATH, mode) {
+        fail(e.raw_os_error());
+    }
+    socket
+}
+
+/// `(uid, gid)` of the process that connected `stream`.
+pub fn credentials(stream: &UnixStream) -> Option<(u32, u32)> {
+    let mut cred = libc::ucred {
+        pid: 0,
+        uid: 0,
+        gid: 0,
+    };
+    let mut len = std::mem::size_of::<libc::ucred>() as libc::socklen_t;
+    // SAFETY: `cred` and `len` are valid out-pointers for SO_PEERCRED.
+    let rc = unsafe {
+        libc::getsockopt(
+            stream.as_raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_PEERCRED,
+            &mut cred as *mut _ as *mut libc::c_void,
+            &mut len,
+        )
+    };
+    (rc == 0 && len as usize == std::mem::size_of::<libc::ucred>()).then_some((cred.uid, cred.gid))
+}
+
+fn allowed((uid, gid): (u32, u32), uids: &[u32], gids: &[u32]) -> bool {
+    uids.contains(&uid) || gids.contains(&gid)
+}
+
+/// True if the CoreSpec admits the peer of `stream`. Unreadable
+/// credentials are not admitted.
+pub fn admitted(stream: &UnixStream) -> bool {
+    credentials(stream).is_some_and(|cred| allowed(cred, INGRESS_PEER_UIDS, INGRESS_PEER_GIDS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::net::UnixListener;
+
+    const NOBODY: u32 = 65534;
+
+    fn own_ids() -> (u32, u32) {
+        // SAFETY: plain id queries.
+        unsafe { (libc::getuid(), libc::getgid()) }
+    }
+
+    #[test]
+    fn socketpair_peer_is_this_process() {
+        let (ours, _theirs) = UnixStream::pair().unwrap();
+        assert_eq!(credentials(&ours), Some(own_ids()));
+    }
+
+    #[test]
+    fn allowlist_admits_by_uid_or_gid() {
+        let (uid, gid) = own_ids();
+        assert!(allowed((uid, gid), &[uid], &[]));
+        assert!(allowed((uid, gid), &[], &[gid]));
+        assert!(!allowed((uid, gid), &[uid.wrapping_add(1)], &[gid.wrapping_add(1)]));
+        assert!(!allowed((uid, gid), &[], &[]));
+    }
+
+    #[test]
+    fn reference_corespec_admits_no_one() {
+        let (ours, _theirs) = UnixStream::pair().unwrap();
+        assert!(!admitted(&ours));
+    }
+
+    #[test]
+    #[ignore = "needs root to connect as nobody"]
+    fn mismatched_credentials_are_rejected() {
+        assert_eq!(own_ids().0, 0, "needs root to connect as nobody");
+        let path = std::env::temp_dir().join(format!("slime-peer-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o666)).unwrap();
+
+        let addr = sockaddr(path.to_str().unwrap());
+        let len = std::mem::size_of::<libc::sockaddr_un>() as libc::socklen_t;
+        // SAFETY: the child only makes raw syscalls and `_exit`s.
+        unsafe {
+            let pid = libc::fork();
+            assert!(pid >= 0);
+            if pid == 0 {
+                let fd = libc::socket(libc::AF_UNIX, libc::SOCK_STREAM, 0);
+                let ok = libc::setgroups(0, std::ptr::null()) == 0
+                    && libc::setgid(NOBODY) == 0
+                    && libc::setuid(NOBODY) == 0
+                    && libc::connect(fd, &addr as *const _ as *const _, len) == 0;
+                libc::_exit(if ok { 0 } else { 1 });
+            }
+            let mut status = 0;
+            assert_eq!(libc::waitpid(pid, &mut status, 0), pid);
+            assert!(libc::WIFEXITED(status) && libc::WEXITSTATUS(status) == 0);
+        }
+
+        // Queued before the child exited; the credentials stay with it.
+        let (stream, _) = listener.accept().unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(credentials(&stream), Some((NOBODY, NOBODY)));
+        assert!(!allowed(credentials(&stream).unwrap(), &[0], &[0]));
+        assert!(allowed(credentials(&stream).unwrap(), &[NOBODY], &[]));
+        assert!(!admitted(&stream));
+    }
+}
@@ -0,0 +1,267 @@
+//! Structured event log on stderr — observation only.
+//!
+//! One JSON object per line, fixed schema (EGRESS_SOCKET_SPEC §Monitoring):
+//!
+//! ```text
+//! {"ts":"2026-02-07T10:23:45Z","level":"WARN","event":"egress_write_failed","errno":32}
+//! ```
+//!
+//! `errno` is present only when an OS error caused the event; `code` only on
+//! `exit`; `check` only on `selfcheck_failed`; `anchor` only on
+//! `audit_checkpoint`. Levels are fixed per event. Nothing here reads
+//! input or changes a decision; a failed stderr write is ignored.
+
+use crate::selfcheck::Check;
+use slime_audit_chain::Anchor;
+use std::io::{self, Write};
+use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const MAX_LINE_BYTES: usize = 256;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Level {
+    Info,
+    Warn,
+    Error,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Event {
+    Boot,
+    SelfCheckFailed(Check),
+    EgressConnected,
+    EgressConnectFailed,
+    EgressWriteFailed,
+    EgressReconnected,
+    AuditOpenFailed,
+    AuditChainUnreadable,
+    AuditWriteFailed,
+    /// A checkpoint reached the audit log; its anchor, for
+    /// `slime-audit verify --anchor`.
+    AuditCheckpoint(Anchor),
+    #[cfg(feature = "dashboard")]
+    ObserveBindFailed,
+    #[cfg(feature = "corespec_ingress_tls")]
+    TlsConfigInvalid,
+    IngressBindFailed,
+    IngressListening,
+    IngressAcceptFailed,
+    AcceptExhausted,
+    LandlockUnavailable,
+    SandboxFailed,
+    SignalSetupFailed,
+    Panic,
+    LockPoisoned,
+    ShutdownRequested,
+    ShutdownGraceExpired,
+    Exit(i32),
+}
+
+impl Level {
+    fn as_str(self) -> &'static str {
+        match self {
+            Level::Info => "INFO",
+            Level::Warn => "WARN",
+            Level::Error => "ERROR",
+        }
+    }
+}
+
+impl Event {
+    fn as_str(self) -> &'static str {
+        match self {
+            Event::Boot => "boot",
+            Event::SelfCheckFailed(_) => "selfcheck_failed",
+            Event::EgressConnected => "egress_connected",
+            Event::EgressConnectFailed => "egress_connect_failed",
+            Event::EgressWriteFailed => "egress_write_failed",
+            Event::EgressReconnected => "egress_reconnected",
+            Event::AuditOpenFailed => "audit_open_failed",
+            Event::AuditChainUnreadable => "audit_chain_unreadable",
+            Event::AuditWriteFailed => "audit_write_failed",
+            Event::AuditCheckpoint(_) => "audit_checkpoint",
+            #[cfg(feature = "dashboard")]
+            Event::ObserveBindFailed => "observe_bind_failed",
+            #[cfg(feature = "corespec_ingress_tls")]
+            Event::TlsConfigInvalid => "tls_config_invalid",
+            Event::IngressBindFailed => "ingress_bind_failed",
+            Event::IngressListening => "ingress_listening",
+            Event::IngressAcceptFailed => "ingress_accept_failed",
+            Event::AcceptExhausted => "accept_exhausted",
+            Event::LandlockUnavailable => "landlock_unavailable",
+            Event::SandboxFailed => "sandbox_failed",
+            Event::SignalSetupFailed => "signal_setup_failed",
+            Event::Panic => "panic",
+            Event::LockPoisoned => "lock_poisoned",
+            Event::ShutdownRequested => "shutdown_requested",
+            Event::ShutdownGraceExpired => "shutdown_grace_expired",
+            Event::Exit(_) => "exit",
+        }
+    }
+
+    fn level(self) -> Level {
+        match self {
+            Event::Boot
+            | Event::EgressConnected
+            | Event::EgressReconnected
+            | Event::IngressListening
+            | Event::AuditCheckpoint(_)
+            | Event::ShutdownRequested
+            | Event::Exit(0) => Level::Info,
+            Event::EgressWriteFailed | Event::AcceptExhausted => Level::Warn,
+            _ => Level::Error,
+        }
+    }
+}
+
+/// Emit one event. `errno` comes from `io::Error::raw_os_error`.
+pub fn emit(event: Event, errno: Option<i32>) {
+    let mut buf = [0u8; MAX_LINE_BYTES];
+    if let Some(len) = encode(event, errno, now_secs(), &mut buf) {
+        let _ = io::stderr().lock().write_all(&buf[..len]);
+    }
+}
+
+/// Emit `event`, then `exit`, tell systemd we are stopping, and terminate
+/// with code 1 (fail-closed).
+pub fn fatal(event: Event, errno: Option<i32>) -> ! {
+    emit(event, errno);
+    emit(Event::Exit(1), None);
+    crate::notify::stopping();
+    process::exit(1)
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Encode one line into `buf` without allocating.
+fn encode(event: Event, errno: Option<i32>, ts: u64, buf: &mut [u8]) -> Option<usize> {
+    let capacity = buf.len();
+    let mut w = &mut buf[..];
+    let (year, month, day) = civil_from_days(ts / 86_400);
+    let secs = ts % 86_400;
+    write!(
+        w,
+        "{{\"ts\":\"{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z\",\"level\":\"{}\",\"event\":\"{}\"",
+        year,
+        month,
+        day,
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60,
+        event.level().as_str(),
+        event.as_str()
+    )
+    .ok()?;
+    if let Some(errno) = errno {
+        write!(w, ",\"errno\":{}", errno).ok()?;
+    }
+    if let Event::Exit(code) = event {
+        write!(w, ",\"code\":{}", code).ok()?;
+    }
+    if let Event::SelfCheckFailed(check) = event {
+        write!(w, ",\"check\":\"{}\"", check.as_str()).ok()?;
+    }
+    if let Event::AuditCheckpoint(anchor) = event {
+        write!(w, ",\"anchor\":\"{}\"", anchor).ok()?;
+    }
+    w.write_all(b"}\n").ok()?;
+    Some(capacity - w.len())
+}
+
+/// Days since 1970-01-01 to a proleptic Gregorian `(year, month, day)`.
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    // Shift the epoch to 0000-03-01 so leap days fall at the end of an era year.
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = era * 400 + yoe + (month <= 2) as u64;
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(event: Event, errno: Option<i32>, ts: u64) -> String {
+        let mut buf = [0u8; MAX_LINE_BYTES];
+        let len = encode(event, errno, ts, &mut buf).unwrap();
+        String::from_utf8(buf[..len].to_vec()).unwrap()
+    }
+
+    #[test]
+    fn encode_matches_spec_example() {
+        // 2026-02-07T10:23:45Z
+        assert_eq!(
+            line(Event::EgressWriteFailed, Some(32), 1_770_459_825),
+            "{\"ts\":\"2026-02-07T10:23:45Z\",\"level\":\"WARN\",\"event\":\"egress_write_failed\",\"errno\":32}\n"
+        );
+    }
+
+    #[test]
+    fn exit_carries_code_and_no_errno() {
+        assert_eq!(
+            line(Event::Exit(1), None, 0),
+            "{\"ts\":\"1970-01-01T00:00:00Z\",\"level\":\"ERROR\",\"event\":\"exit\",\"code\":1}\n"
+        );
+    }
+
+    #[test]
+    fn clean_exit_is_info() {
+        assert!(line(Event::Exit(0), None, 0).contains("\"level\":\"INFO\",\"event\":\"exit\",\"code\":0}"));
+    }
+
+    #[test]
+    fn selfcheck_failure_names_the_check() {
+        assert_eq!(
+            line(Event::SelfCheckFailed(Check::MemoryLock), Some(12), 0),
+            "{\"ts\":\"1970-01-01T00:00:00Z\",\"level\":\"ERROR\",\"event\":\"selfcheck_failed\",\"errno\":12,\"check\":\"memory_lock\"}\n"
+        );
+    }
+
+    #[test]
+    fn checkpoint_carries_its_anchor() {
+        let anchor = Anchor {
+            seq: 129,
+            hash: [0xab; 32],
+        };
+        assert_eq!(
+            line(Event::AuditCheckpoint(anchor), None, 0),
+            format!(
+                "{{\"ts\":\"1970-01-01T00:00:00Z\",\"level\":\"INFO\",\"event\":\"audit_checkpoint\",\"anchor\":\"129:{}\"}}\n",
+                "ab".repeat(32)
+            )
+        );
+    }
+
+    #[test]
+    fn civil_from_days_known_answers() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+        assert_eq!(civil_from_days(20_491), (2026, 2, 7));
+    }
+
+    #[test]
+    fn worst_case_line_fits_buffer() {
+        let mut buf = [0u8; MAX_LINE_BYTES];
+        assert!(encode(Event::AuditChainUnreadable, Some(i32::MIN), u64::MAX / 2, &mut buf).is_some());
+        assert!(encode(Event::Exit(i32::MIN), Some(i32::MIN), u64::MAX / 2, &mut buf).is_some());
+        let longest = Event::SelfCheckFailed(Check::Capabilities);
+        assert!(encode(longest, Some(i32::MIN), u64::MAX / 2, &mut buf).is_some());
+        let anchor = Anchor {
+            seq: u64::MAX,
+            hash: [0xff; 32],
+        };
+        assert!(encode(Event::AuditCheckpoint(anchor), Some(i32::MIN), u64::MAX / 2, &mut buf).is_some());
+    }
+}
@@ -1,71 +1,31 @@
 use std::io::{Read, Write};
-use std::net::{TcpListener, TcpStream};
+use std::net::TcpStream;
 use std::os::unix::net::UnixStream;
-use std::process;
 use std::sync::{Mutex, OnceLock};
-use std::time::Duration;
-
-// ---------------------------------------------------------------------------
-// Conditional resolver: real AB-S engine or stub
-// ---------------------------------------------------------------------------
-
-// Compile-time guard: exactly one resolver must be selected.
-#[cfg(all(feature = "stub_ab", feature = "real_ab"))]
-compile_error!("Features `stub_ab` and `real_ab` are mutually exclusive");
-
-#[cfg(not(any(feature = "stub_ab", feature = "real_ab")))]
-compile_error!("Either `stub_ab` or `real_ab` feature must be enabled");
-
-// Real AB-S engine (private dependency, not shipped with open-source SLIME)
-#[cfg(feature = "real_ab")]
-use anathema_breaker_core::pom::topology::Action as AbAction;
-#[cfg(feature = "real_ab")]
-use anathema_breaker_core::pom::topology::RZ;
-#[cfg(feature = "real_ab")]
-use anathema_breaker_core::pom::types::{Budget, Capacity, Domain, Magnitude, Progression};
-#[cfg(feature = "real_ab")]
-use anathema_breaker_core::pom::resolve_action::resolve_action;
-
-// Stub resolver (default for open-source builds)
-#[cfg(feature = "stub_ab")]
-mod stub_resolver {
-    //! Reference-only action resolver — NOT the real law engine.
-    //!
-    //! Simple capacity check: known domain + magnitude ≤ capacity → AUTHORIZED.
-    //! The real engine (Anathema-Breaker) uses formal typestate topology
-    //! (RZ → EP → IZ) and is not included in the open-source distribution.
-
-    #[derive(Clone, Copy)]
-    pub struct Domain(pub u16);
-
-    #[derive(Clone, Copy)]
-    pub struct Magnitude(pub u32);
-
-    #[derive(Clone, Copy)]
-    pub struct Capacity(pub u32);
-
-    #[derive(Clone, Copy)]
-    pub struct Progression(pub u32);
-
-    pub struct Budget {
-        pub capacity: Capacity,
-        pub progression: Progression,
-    }
-
-    /// Stub resolver: magnitude ≤ capacity → AUTHORIZED, else IMPOSSIBLE.
-    /// Budget is decremented on success (fresh per request in V1).
-    pub fn resolve(domain: Domain, magnitude: Magnitude, budget: &mut Budget) -> Option<u32> {
-        let _ = domain; // domain validity already checked by resolve_domain()
-        if magnitude.0 > budget.capacity.0 {
-            return None;
-        }
-        budget.capacity = Capacity(budget.capacity.0.saturating_sub(magnitude.0));
-        Some(magnitude.0)
-    }
-}
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-#[cfg(feature = "stub_ab")]
-use stub_resolver::{Budget, Capacity, Domain, Magnitude, Progression};
+mod accept;
+#[cfg(test)]
+mod alloc_guard;
+mod audit;
+mod failstop;
+#[cfg(feature = "corespec_ingress_unix")]
+mod local;
+mod log;
+mod notify;
+mod observe;
+mod sandbox;
+mod selfcheck;
+mod shutdown;
+#[cfg(feature = "corespec_ingress_tls")]
+mod tls;
+
+use slime_abi::{AuthorizedEffect, EGRESS_SOCKET_PATH, FRAME_BYTES};
+use slime_core::{
+    authorized_effect, parse_request, parse_request_cbor, LawPart, LawRequest, Refusal, Rejection,
+    WeekTime,
+};
+use slime_core::corespec::MAX_COMPOSITE_PARTS;
 
 //
 // -------------------- Hardening Constants (Phase 2) --------------------
@@ -74,99 +34,139 @@ use stub_resolver::{Budget, Capacity, Domain, Magnitude, Progression};
 const MAX_HEADER_BYTES: usize = 8 * 1024;
 const MAX_BODY_BYTES: usize = 64 * 1024;
 const READ_TIMEOUT_SECS: u64 = 2;
+/// One request, header and body, read in place.
+const REQUEST_BUF_BYTES: usize = MAX_HEADER_BYTES + MAX_BODY_BYTES;
+/// One whole response: status line, headers and verdict.
+const MAX_RESPONSE_BYTES: usize = 128;
+/// Open ingress connections: the one being served plus the listen queue
+/// (Linux queues up to backlog + 1). Past that the kernel holds new
+/// clients off rather than refusing them: TCP SYNs are dropped and
+/// retried by the client, a Unix-socket connect blocks or fails with
+/// `EAGAIN`. None of them is accepted until the queue has room.
+const MAX_OPEN_CONNECTIONS: usize = 64;
+const _: () = assert!(MAX_OPEN_CONNECTIONS >= 2);
+
+/// Position in the UTC week, for the law's time windows.
+/// `None` if the clock reads before the epoch.
+fn week_time_now() -> Option<WeekTime> {
+    let since_epoch = SystemTime::now().duration_since(UNIX_EPOCH).ok()?;
+    Some(WeekTime::from_unix(since_epoch.as_secs()))
+}
 
 //
-// -------------------- CoreSpec Constants (Phase 6.3) --------------------
-// Compile-time law. No runtime configuration. No env vars.
-// Change these constants = produce a different binary = different CoreSpec.
+// -------------------- Saturating Ledger (opt-in CoreSpec) --------------------
+//
+// The core ledger, held for the process lifetime.
 //
 
-/// Domain mapping table — sealed at compile time.
-/// Unknown domains are structurally impossible.
-const DOMAIN_TABLE: &[(&str, u16)] = &[
-    ("test", 0),
-    ("payment", 1),
-    ("deploy", 2),
-    ("db_prod", 3),
-];
-
-/// Budget constants — fresh budget per request (V1 statelessness).
-/// No state persists between requests.
-const CORESPEC_CAPACITY: u32 = 10_000;
-const CORESPEC_PROGRESSION: u32 = 1;
-
-// -------------------- Types --------------------
-
-#[derive(Clone, Copy)]
-struct AuthorizedEffect {
-    domain_id: u64,
-    magnitude: u64,
-    actuation_token: u128,
-}
+#[cfg(feature = "corespec_saturating")]
+mod saturation {
+    use super::*;
+    use slime_core::saturation::Ledger;
+
+    static LEDGER: OnceLock<Mutex<Ledger>> = OnceLock::new();
 
-struct ActionRequest {
-    domain: [u8; 64],
-    domain_len: usize,
-    magnitude: u64,
+    pub fn resolve(
+        parts: &[LawPart],
+        now: Option<WeekTime>,
+    ) -> Result<[u32; MAX_COMPOSITE_PARTS], Refusal> {
+        let ledger = LEDGER.get_or_init(|| Mutex::new(Ledger::new()));
+        let mut guard = crate::failstop::lock(ledger);
+        guard.resolve(parts, now)
+    }
 }
 
 //
-// -------------------- Domain Resolution (Phase 6.3) --------------------
+// -------------------- Ingress Read (Hardened) --------------------
 //
 
-fn resolve_domain(name: &str) -> Option<Domain> {
-    for &(key, id) in DOMAIN_TABLE {
-        if key == name {
-            return Some(Domain(id));
-        }
+/// An accepted ingress connection: TCP, or the Unix socket of the
+/// `corespec_ingress_unix` CoreSpec, or TLS over TCP with
+/// `corespec_ingress_tls`.
+trait Conn: Read + Write {
+    fn set_blocking_with_timeout(&self, timeout: Duration) -> std::io::Result<()>;
+    /// Whether the CoreSpec admits the peer. TCP carries no credentials.
+    fn peer_admitted(&mut self) -> bool {
+        true
     }
-    None
+    /// After the reply, before the connection is dropped.
+    fn finish(&mut self) {}
+    /// Whether a peer refused by `peer_admitted` is sent the verdict.
+    const REPLIES_TO_REFUSED_PEER: bool = true;
 }
 
-fn domain_to_egress_id(d: Domain) -> u64 {
-    d.0 as u64
+impl Conn for TcpStream {
+    fn set_blocking_with_timeout(&self, timeout: Duration) -> std::io::Result<()> {
+        self.set_nonblocking(false)?;
+        self.set_read_timeout(Some(timeout))
+    }
 }
 
-//
-// -------------------- Law Resolution Wrapper --------------------
-//
-
-/// Resolve an action through the selected law engine.
-/// Returns the applied magnitude on AUTHORIZED, or None on IMPOSSIBLE.
-fn resolve_law(domain: Domain, magnitude: Magnitude, budget: &mut Budget) -> Option<u32> {
-    #[cfg(feature = "real_ab")]
-    {
-        let action = AbAction::<RZ>::new(domain, magnitude);
-        match resolve_action(action, budget) {
-            Ok(effect) => Some(effect.magnitude_applied.0),
-            Err(_impossibility) => None,
-        }
+#[cfg(feature = "corespec_ingress_unix")]
+impl Conn for UnixStream {
+    fn set_blocking_with_timeout(&self, timeout: Duration) -> std::io::Result<()> {
+        self.set_nonblocking(false)?;
+        self.set_read_timeout(Some(timeout))
     }
-    #[cfg(feature = "stub_ab")]
-    {
-        stub_resolver::resolve(domain, magnitude, budget)
+
+    fn peer_admitted(&mut self) -> bool {
+        crate::local::admitted(self)
     }
 }
 
-//
-// -------------------- Ingress Read (Hardened) --------------------
-//
+#[cfg(feature = "corespec_ingress_tls")]
+impl Conn for crate::tls::Stream {
+    fn set_blocking_with_timeout(&self, timeout: Duration) -> std::io::Result<()> {
+        self.sock.set_blocking_with_timeout(timeout)
+    }
+
+    fn peer_admitted(&mut self) -> bool {
+        crate::tls::handshake(self)
+    }
+
+    fn finish(&mut self) {
+        crate::tls::close(self)
+    }
+
+    /// A refused handshake leaves no session to carry a reply.
+    const REPLIES_TO_REFUSED_PEER: bool = false;
+}
 
-fn read_http_body_hardened(stream: &mut TcpStream) -> Option<Vec<u8>> {
-    let _ = stream.set_read_timeout(Some(Duration::from_secs(READ_TIMEOUT_SECS)));
+/// Body encoding, from `Content-Type`. `application/cbor` is CBOR;
+/// anything else, or no header at all, is read as JSON.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Encoding {
+    Json,
+    Cbor,
+}
 
-    let mut buf = Vec::<u8>::new();
-    let mut tmp = [0u8; 1024];
+/// Value of the first header named `name`, any case.
+fn header_value<'a>(header_text: &'a str, name: &str) -> Option<&'a str> {
+    header_text.lines().find_map(|l| {
+        let (n, value) = l.split_once(':')?;
+        n.eq_ignore_ascii_case(name).then_some(value)
+    })
+}
 
+/// Read one request into `buf` in place and return its body and encoding.
+/// Header bytes land in `buf[..MAX_HEADER_BYTES]`; the body follows the
+/// header terminator and is at most `MAX_BODY_BYTES` (V1 §8: no growth).
+fn read_http_body_hardened<'a>(
+    stream: &mut impl Read,
+    buf: &'a mut [u8; REQUEST_BUF_BYTES],
+) -> Option<(Encoding, &'a [u8])> {
+    let mut filled = 0;
     let mut header_end = None;
-    while buf.len() < MAX_HEADER_BYTES {
-        let n = stream.read(&mut tmp).ok()?;
+    while filled < MAX_HEADER_BYTES {
+        let n = stream.read(&mut buf[filled..MAX_HEADER_BYTES]).ok()?;
         if n == 0 {
             return None;
         }
-        buf.extend_from_slice(&tmp[..n]);
-        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
-            header_end = Some(pos + 4);
+        // A terminator may straddle two reads.
+        let from = filled.saturating_sub(3);
+        filled += n;
+        if let Some(pos) = buf[from..filled].windows(4).position(|w| w == b"\r\n\r\n") {
+            header_end = Some(from + pos + 4);
             break;
         }
     }
@@ -177,73 +177,29 @@ fn read_http_body_hardened(stream: &mut TcpStream) -> Option<Vec<u8>> {
     }
 
     let header_text = std::str::from_utf8(&buf[..header_end]).ok()?;
-    let content_length = header_text
-        .lines()
-        .find(|l| l.to_ascii_lowercase().starts_with("content-length:"))
-        .and_then(|l| l.splitn(2, ':').nth(1))
+    let content_length = header_value(header_text, "content-length")
         .and_then(|v| v.trim().parse::<usize>().ok())?;
+    let media_type = header_value(header_text, "content-type")
+        .map(|v| v.split(';').next().unwrap_or("").trim());
+    let encoding = match media_type {
+        Some(t) if t.eq_ignore_ascii_case("application/cbor") => Encoding::Cbor,
+        _ => Encoding::Json,
+    };
 
     if content_length > MAX_BODY_BYTES {
         return None;
     }
 
-    let mut body = Vec::with_capacity(content_length);
-    let already_read = &buf[header_end..];
-    let preloaded = already_read.len().min(content_length);
-    body.extend_from_slice(&already_read[..preloaded]);
-    while body.len() < content_length {
-        let remaining = content_length - body.len();
-        let chunk = remaining.min(tmp.len());
-        let n = stream.read(&mut tmp[..chunk]).ok()?;
+    let end = header_end + content_length;
+    while filled < end {
+        let n = stream.read(&mut buf[filled..end]).ok()?;
         if n == 0 {
             return None;
         }
-        body.extend_from_slice(&tmp[..n]);
+        filled += n;
     }
 
-    Some(body)
-}
-
-//
-// -------------------- Request Parse --------------------
-//
-
-fn parse_request(body: &[u8]) -> Option<ActionRequest> {
-    let text = std::str::from_utf8(body).ok()?;
-
-    let domain_str = {
-        let p = text.find("\"domain\"")?;
-        let s = &text[p..];
-        let q1 = s.find('"')?;
-        let s2 = &s[q1 + 1..];
-        let q2 = s2.find('"')?;
-        let s3 = &s2[q2 + 1..];
-        let q3 = s3.find('"')?;
-        let s4 = &s3[q3 + 1..];
-        let q4 = s4.find('"')?;
-        &s4[..q4]
-    };
-
-    let magnitude = {
-        let p = text.find("\"magnitude\":")?;
-        let s = &text[p + 12..];
-        s.trim_start()
-            .chars()
-            .take_while(|c| c.is_ascii_digit())
-            .collect::<String>()
-            .parse::<u64>()
-            .ok()?
-    };
-
-    let mut domain = [0u8; 64];
-    let domain_len = domain_str.len().min(64);
-    domain[..domain_len].copy_from_slice(&domain_str.as_bytes()[..domain_len]);
-
-    Some(ActionRequest {
-        domain,
-        domain_len,
-        magnitude,
-    })
+    Some((encoding, &buf[header_end..end]))
 }
 
 //
@@ -252,37 +208,82 @@ fn parse_request(body: &[u8]) -> Option<ActionRequest> {
 
 mod egress {
     use super::*;
+    use std::sync::atomic::Ordering;
 
-    const SOCKET_PATH: &str = "/run/slime/egress.sock";
     static STREAM: OnceLock<Mutex<UnixStream>> = OnceLock::new();
 
     pub fn init_fail_closed() {
-        let s = UnixStream::connect(SOCKET_PATH).unwrap_or_else(|_| {
-            process::exit(1);
+        let s = UnixStream::connect(EGRESS_SOCKET_PATH).unwrap_or_else(|e| {
+            log::fatal(log::Event::EgressConnectFailed, e.raw_os_error());
         });
         let _ = STREAM.set(Mutex::new(s));
+        crate::observe::EGRESS_CONNECTED.store(true, Ordering::Relaxed);
+        log::emit(log::Event::EgressConnected, None);
     }
 
-    pub fn apply(effect: AuthorizedEffect) {
+    /// Write the frames of one authorized request. A composite request's
+    /// frames go out in a single write under one lock hold, so they stay
+    /// contiguous on the socket.
+    pub fn apply(effects: &[AuthorizedEffect]) {
         let stream = STREAM.get();
         if stream.is_none() {
-            process::exit(1);
+            log::fatal(log::Event::EgressConnectFailed, None);
         }
-        let mut guard = stream.unwrap().lock().unwrap();
-
-        let mut buf = [0u8; 32];
-        buf[0..8].copy_from_slice(&effect.domain_id.to_le_bytes());
-        buf[8..16].copy_from_slice(&effect.magnitude.to_le_bytes());
-        buf[16..32].copy_from_slice(&effect.actuation_token.to_le_bytes());
+        let mut guard = crate::failstop::lock(stream.unwrap());
 
-        if guard.write_all(&buf).is_err() {
-            let s = UnixStream::connect(SOCKET_PATH).unwrap_or_else(|_| process::exit(1));
+        let mut frames = [0u8; FRAME_BYTES * MAX_COMPOSITE_PARTS];
+        for (frame, effect) in frames.chunks_exact_mut(FRAME_BYTES).zip(effects) {
+            frame.copy_from_slice(&effect.encode());
+        }
+        let buf = &frames[..FRAME_BYTES * effects.len().min(MAX_COMPOSITE_PARTS)];
+        #[cfg(test)]
+        crate::failstop::fault(crate::failstop::Point::Egress);
+
+        if let Err(e) = guard.write_all(buf) {
+            log::emit(log::Event::EgressWriteFailed, e.raw_os_error());
+            crate::observe::bump(&crate::observe::EGRESS_WRITE_FAILURES);
+            crate::observe::EGRESS_CONNECTED.store(false, Ordering::Relaxed);
+            let s = UnixStream::connect(EGRESS_SOCKET_PATH).unwrap_or_else(|e| {
+                log::fatal(log::Event::EgressConnectFailed, e.raw_os_error());
+            });
             *guard = s;
-            if guard.write_all(&buf).is_err() {
-                process::exit(1);
+            log::emit(log::Event::EgressReconnected, None);
+            crate::observe::bump(&crate::observe::EGRESS_RECONNECTS);
+            crate::observe::EGRESS_CONNECTED.store(true, Ordering::Relaxed);
+            if let Err(e) = guard.write_all(buf) {
+                log::fatal(log::Event::EgressWriteFailed, e.raw_os_error());
             }
         }
     }
+
+    /// Push out anything the kernel still holds and half-close, so the
+    /// actuator sees EOF right after the last frame.
+    pub fn flush() {
+        if let Some(stream) = STREAM.get() {
+            let mut guard = crate::failstop::lock(stream);
+            let _ = guard.flush();
+            let _ = guard.shutdown(std::net::Shutdown::Write);
+        }
+    }
+
+    /// Egress backed by a socketpair whose peer is drained on a thread.
+    /// Lets tests reach the AUTHORIZED path without `/run/slime`.
+    #[cfg(test)]
+    pub fn connect_for_test() {
+        STREAM.get_or_init(|| {
+            let (ours, mut peer) = UnixStream::pair().unwrap();
+            std::thread::spawn(move || {
+                let _ = std::io::copy(&mut peer, &mut std::io::sink());
+            });
+            Mutex::new(ours)
+        });
+    }
+
+    /// Egress to a socket the test holds the peer of.
+    #[cfg(test)]
+    pub fn use_for_test(stream: UnixStream) {
+        let _ = STREAM.set(Mutex::new(stream));
+    }
 }
 
 //
@@ -291,80 +292,272 @@ mod egress {
 
 mod ingress {
     use super::*;
+    use std::os::fd::AsRawFd;
     const AUTHORIZED_STATUS: &[u8] = b"{\"status\":\"AUTHORIZED\"}";
     const IMPOSSIBLE_STATUS: &[u8] = b"{\"status\":\"IMPOSSIBLE\"}";
 
-    fn write_status_response(stream: &mut TcpStream, status: &[u8]) {
-        let header = format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n", status.len());
-        let _ = stream.write_all(header.as_bytes());
-        let _ = stream.write_all(status);
+    fn write_status_response(stream: &mut impl Write, status: &[u8]) {
+        let mut buf = [0u8; MAX_RESPONSE_BYTES];
+        let capacity = buf.len();
+        let mut w = &mut buf[..];
+        if write!(w, "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n", status.len()).is_err()
+            || w.write_all(status).is_err()
+        {
+            return;
+        }
+        let len = capacity - w.len();
+        let _ = stream.write_all(&buf[..len]);
+    }
+
+    /// Size the listen queue to the connection cap. On a socket that is
+    /// already listening, listen(2) only resizes the queue.
+    fn listen_fail_closed(fd: std::os::fd::RawFd) {
+        let backlog = (MAX_OPEN_CONNECTIONS - 2) as libc::c_int;
+        // SAFETY: plain call on a socket we own.
+        if unsafe { libc::listen(fd, backlog) } != 0 {
+            let errno = std::io::Error::last_os_error().raw_os_error();
+            log::fatal(log::Event::IngressBindFailed, errno);
+        }
     }
 
+    /// Serve `127.0.0.1:8080` until shutdown is requested. Returns with no
+    /// request in flight.
+    #[cfg(not(feature = "corespec_ingress_unix"))]
     pub fn start() {
-        let listener = TcpListener::bind("127.0.0.1:8080").unwrap();
-        for conn in listener.incoming() {
-            if let Ok(stream) = conn {
-                handle(stream);
+        #[cfg(feature = "corespec_ingress_tls")]
+        let tls = crate::tls::config_fail_closed();
+        let listener = std::net::TcpListener::bind("127.0.0.1:8080").unwrap_or_else(|e| {
+            log::fatal(log::Event::IngressBindFailed, e.raw_os_error());
+        });
+        // Non-blocking: the acceptor waits in poll(2), and a connection
+        // gone between the wakeup and accept(2) must not block the loop.
+        listener.set_nonblocking(true).unwrap_or_else(|e| {
+            log::fatal(log::Event::IngressBindFailed, e.raw_os_error());
+        });
+        listen_fail_closed(listener.as_raw_fd());
+        #[cfg(feature = "corespec_ingress_tls")]
+        let listener = crate::tls::Listener::new(listener, tls);
+        serve(crate::accept::Acceptor::new(listener));
+    }
+
+    /// Serve the socket from `local::bind_fail_closed` instead of TCP.
+    #[cfg(feature = "corespec_ingress_unix")]
+    pub fn start_local(socket: std::os::fd::OwnedFd) {
+        listen_fail_closed(socket.as_raw_fd());
+        let listener = std::os::unix::net::UnixListener::from(socket);
+        listener.set_nonblocking(true).unwrap_or_else(|e| {
+            log::fatal(log::Event::IngressBindFailed, e.raw_os_error());
+        });
+        serve(crate::accept::Acceptor::new(listener));
+    }
+
+    fn serve<L>(mut acceptor: crate::accept::Acceptor<L>)
+    where
+        L: crate::accept::Listen,
+        L::Stream: Conn,
+    {
+        // Last boot step: from here on only the syscall allowlist remains.
+        crate::sandbox::seccomp_fail_closed();
+        log::emit(log::Event::IngressListening, None);
+        crate::notify::ready();
+
+        let timeout = Duration::from_secs(READ_TIMEOUT_SECS);
+        let mut watchdog = crate::notify::Watchdog::start();
+        while !crate::shutdown::requested() {
+            match acceptor.next() {
+                Ok(Some(stream)) => {
+                    if stream.set_blocking_with_timeout(timeout).is_ok() {
+                        handle(stream);
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => log::fatal(log::Event::IngressAcceptFailed, e.raw_os_error()),
             }
+            watchdog.tick();
         }
     }
 
-    fn handle(mut stream: TcpStream) {
-        let body = match crate::read_http_body_hardened(&mut stream) {
-            Some(b) => b,
-            None => {
-                write_status_response(&mut stream, IMPOSSIBLE_STATUS);
-                return;
-            }
-        };
+    /// Result of evaluating one request. Only the verdict reaches the
+    /// client; the rest is for egress and the audit trail.
+    enum Outcome {
+        Authorized {
+            law: LawRequest,
+            applied: [u32; MAX_COMPOSITE_PARTS],
+        },
+        /// Refused by the law: one audit record per part.
+        Refused { law: LawRequest, refusal: Refusal },
+        /// Died before the law.
+        Rejected(audit::Record),
+    }
 
-        let req = match crate::parse_request(&body) {
-            Some(r) => r,
-            None => {
-                write_status_response(&mut stream, IMPOSSIBLE_STATUS);
-                return;
+    fn handle<S: Conn>(mut stream: S) {
+        crate::observe::bump(&crate::observe::REQUESTS);
+        let mut buf = [0u8; REQUEST_BUF_BYTES];
+        let outcome = evaluate(&mut stream, &mut buf);
+        // On disk before any effect leaves: no frame reaches egress
+        // without the record that authorized it.
+        audit_outcome(&outcome);
+
+        let status = match &outcome {
+            Outcome::Authorized { law, applied } => {
+                let mut effects = [AuthorizedEffect::EMPTY; MAX_COMPOSITE_PARTS];
+                let authorized = effects.iter_mut().zip(law.parts()).zip(applied);
+                for ((effect, part), applied_mag) in authorized {
+                    *effect = authorized_effect(part, *applied_mag);
+                }
+                crate::egress::apply(&effects[..law.count]);
+                #[cfg(test)]
+                crate::failstop::fault(crate::failstop::Point::Reply);
+                crate::observe::bump(&crate::observe::AUTHORIZED);
+                AUTHORIZED_STATUS
+            }
+            Outcome::Refused { .. } => {
+                crate::observe::impossible_at(audit::Stage::Law);
+                IMPOSSIBLE_STATUS
+            }
+            Outcome::Rejected(rec) => {
+                crate::observe::impossible_at(rec.stage);
+                IMPOSSIBLE_STATUS
             }
         };
 
-        // -- Law Resolution -----------------------------------------------
-        //
-        // 1. Resolve domain via sealed compile-time table
-        let domain_str = std::str::from_utf8(&req.domain[..req.domain_len]).unwrap_or("");
-        let domain = match crate::resolve_domain(domain_str) {
-            Some(d) => d,
-            None => {
-                write_status_response(&mut stream, IMPOSSIBLE_STATUS);
+        if let Outcome::Rejected(rec) = &outcome {
+            if rec.stage == audit::Stage::Peer && !S::REPLIES_TO_REFUSED_PEER {
                 return;
             }
+        }
+        write_status_response(&mut stream, status);
+        stream.finish();
+    }
+
+    fn audit_outcome(outcome: &Outcome) {
+        let law_record = |part: &LawPart, magnitude: u32, verdict, reason| audit::Record {
+            stage: audit::Stage::Law,
+            domain_id: Some(slime_core::domain_to_egress_id(part.domain)),
+            magnitude: Some(audit::Magnitude::Scaled(magnitude as u64)),
+            verdict,
+            reason,
         };
+        match outcome {
+            Outcome::Authorized { law, applied } => {
+                for (part, applied_mag) in law.parts().iter().zip(applied) {
+                    audit::record(&law_record(
+                        part,
+                        *applied_mag,
+                        audit::Verdict::Authorized,
+                        audit::Reason::Authorized,
+                    ));
+                }
+            }
+            Outcome::Refused { law, refusal } => {
+                for part in law.parts() {
+                    audit::record(&law_record(
+                        part,
+                        part.magnitude,
+                        audit::Verdict::Impossible,
+                        audit::Reason::from(*refusal),
+                    ));
+                }
+            }
+            Outcome::Rejected(rec) => audit::record(rec),
+        }
+    }
 
-        // 2. Validate magnitude fits u32 (AB-S uses Magnitude(u32))
-        if req.magnitude == 0 || req.magnitude > u32::MAX as u64 {
-            write_status_response(&mut stream, IMPOSSIBLE_STATUS);
-            return;
+    /// Approved request signers. Unit tests approve the RFC 8032 §7.1
+    /// TEST 1 key instead, so a signed request can reach the law.
+    #[cfg(all(feature = "corespec_signed_requests", not(test)))]
+    const SIGNING_KEYS: &[[u8; 32]] = slime_core::corespec::REQUEST_SIGNING_KEYS;
+    #[cfg(all(feature = "corespec_signed_requests", test))]
+    const SIGNING_KEYS: &[[u8; 32]] = &[[
+        0xd7, 0x5a, 0x98, 0x01, 0x82, 0xb1, 0x0a, 0xb7, 0xd5, 0x4b, 0xfe, 0xd3, 0xc9, 0x64, 0x07,
+        0x3a, 0x0e, 0xe1, 0x72, 0xf3, 0xda, 0xa6, 0x23, 0x25, 0xaf, 0x02, 0x1a, 0x68, 0xf7, 0x07,
+        0x51, 0x1a,
+    ]];
+
+    /// Read, parse and resolve one request. Writes nothing to the client.
+    fn evaluate(stream: &mut impl Conn, buf: &mut [u8; REQUEST_BUF_BYTES]) -> Outcome {
+        if !stream.peer_admitted() {
+            return Outcome::Rejected(audit::Record::rejected(
+                audit::Stage::Peer,
+                audit::Reason::PeerCredentials,
+            ));
         }
-        let magnitude = Magnitude(req.magnitude as u32);
 
-        // 3. Fresh budget per request (V1 statelessness)
-        let mut budget = Budget {
-            capacity: Capacity(CORESPEC_CAPACITY),
-            progression: Progression(CORESPEC_PROGRESSION),
+        let Some((encoding, body)) = crate::read_http_body_hardened(stream, buf) else {
+            return Outcome::Rejected(audit::Record::rejected(
+                audit::Stage::Header,
+                audit::Reason::HeaderRead,
+            ));
         };
 
-        // 4. Resolve through selected law engine (real AB-S or stub)
-        match crate::resolve_law(domain, magnitude, &mut budget) {
-            Some(applied_mag) => {
-                let authorized = AuthorizedEffect {
-                    domain_id: crate::domain_to_egress_id(domain),
-                    magnitude: applied_mag as u64,
-                    actuation_token: 0u128,
-                };
-                crate::egress::apply(authorized);
-                write_status_response(&mut stream, AUTHORIZED_STATUS);
+        let parsed = match encoding {
+            crate::Encoding::Json => parse_request(body),
+            crate::Encoding::Cbor => parse_request_cbor(body),
+        };
+        let Some(req) = parsed else {
+            return Outcome::Rejected(audit::Record::rejected(
+                audit::Stage::Parse,
+                audit::Reason::Malformed,
+            ));
+        };
+
+        // Signed-request CoreSpec: no approved signature, no domain lookup.
+        #[cfg(feature = "corespec_signed_requests")]
+        if !slime_core::signature::verify_with(&req, SIGNING_KEYS) {
+            return Outcome::Rejected(audit::Record::rejected(
+                audit::Stage::Parse,
+                audit::Reason::SignatureInvalid,
+            ));
+        }
+
+        // -- Law Resolution -----------------------------------------------
+        //
+        // 1. Resolve every domain, 2. scale every magnitude; any failure
+        //    makes the whole request IMPOSSIBLE and nothing reaches egress.
+        let law = match slime_core::admit(&req) {
+            Ok(law) => law,
+            Err(Rejection::UnknownDomain) => {
+                return Outcome::Rejected(audit::Record::rejected(
+                    audit::Stage::Domain,
+                    audit::Reason::UnknownDomain,
+                ))
+            }
+            Err(Rejection::DuplicateDomain { domain_id }) => {
+                return Outcome::Rejected(audit::Record {
+                    domain_id: Some(domain_id),
+                    ..audit::Record::rejected(
+                        audit::Stage::Domain,
+                        audit::Reason::DuplicateDomain,
+                    )
+                })
             }
-            None => {
-                write_status_response(&mut stream, IMPOSSIBLE_STATUS);
+            Err(Rejection::MagnitudeRange { domain_id, written }) => {
+                return Outcome::Rejected(audit::Record {
+                    domain_id: Some(domain_id),
+                    magnitude: Some(audit::Magnitude::Written(written)),
+                    ..audit::Record::rejected(
+                        audit::Stage::Magnitude,
+                        audit::Reason::MagnitudeRange,
+                    )
+                })
             }
+        };
+
+        // 3. One fresh budget shared by the parts (V1 statelessness), or
+        //    the lifetime ledger in the saturating CoreSpec
+        // 4. Resolve through selected law engine (real AB-S or stub),
+        //    gated by the CoreSpec time windows at the current UTC time
+        let now = crate::week_time_now();
+        #[cfg(not(feature = "corespec_saturating"))]
+        let resolved = slime_core::resolve_composite(law.parts(), now);
+        #[cfg(feature = "corespec_saturating")]
+        let resolved = crate::saturation::resolve(law.parts(), now);
+        #[cfg(test)]
+        crate::failstop::fault(crate::failstop::Point::Decision);
+
+        match resolved {
+            Ok(applied) => Outcome::Authorized { law, applied },
+            Err(refusal) => Outcome::Refused { law, refusal },
         }
     }
 
@@ -423,6 +616,503 @@ mod ingress {
             let text = String::from_utf8(resp).unwrap();
             assert!(text.contains("{\"status\":\"IMPOSSIBLE\"}"));
         }
+
+        /// Serve one raw request through `f` on a loopback socket; return
+        /// what `f` produced and the bytes the client received.
+        fn serve_once<T: Send + 'static>(
+            raw: &'static [u8],
+            f: fn(TcpStream) -> T,
+        ) -> (T, Vec<u8>) {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let addr = listener.local_addr().unwrap();
+
+            let t = std::thread::spawn(move || {
+                let (stream, _) = listener.accept().unwrap();
+                f(stream)
+            });
+
+            let mut client = TcpStream::connect(addr).unwrap();
+            let _ = client.write_all(raw);
+            let _ = client.shutdown(std::net::Shutdown::Write);
+
+            let mut resp = Vec::new();
+            let _ = client.read_to_end(&mut resp);
+            (t.join().unwrap(), resp)
+        }
+
+        /// One request per pre-law stage plus a law refusal.
+        const REJECTIONS: [(&[u8], audit::Stage, audit::Reason); 5] = [
+            (
+                b"POST / HTTP/1.1\r\nHost: localhost\r\n\r\n{}",
+                audit::Stage::Header,
+                audit::Reason::HeaderRead,
+            ),
+            (
+                b"POST / HTTP/1.1\r\nContent-Length: 2\r\n\r\n{}",
+                audit::Stage::Parse,
+                audit::Reason::Malformed,
+            ),
+            (
+                b"POST / HTTP/1.1\r\nContent-Length: 31\r\n\r\n{\"domain\":\"nope\",\"magnitude\":1}",
+                audit::Stage::Domain,
+                audit::Reason::UnknownDomain,
+            ),
+            (
+                b"POST / HTTP/1.1\r\nContent-Length: 31\r\n\r\n{\"domain\":\"test\",\"magnitude\":0}",
+                audit::Stage::Magnitude,
+                audit::Reason::MagnitudeRange,
+            ),
+            (
+                b"POST / HTTP/1.1\r\nContent-Length: 35\r\n\r\n{\"domain\":\"test\",\"magnitude\":20000}",
+                audit::Stage::Law,
+                audit::Reason::LawImpossible,
+            ),
+        ];
+
+        fn trace(outcome: Outcome) -> (audit::Stage, audit::Reason) {
+            match outcome {
+                Outcome::Rejected(rec) => (rec.stage, rec.reason),
+                Outcome::Refused { refusal, .. } => (audit::Stage::Law, audit::Reason::from(refusal)),
+                Outcome::Authorized { .. } => (audit::Stage::Law, audit::Reason::Authorized),
+            }
+        }
+
+        // Unsigned requests stop at parse when requests must be signed.
+        #[cfg(not(feature = "corespec_signed_requests"))]
+        #[test]
+        fn audit_trace_distinguishes_every_stage() {
+            for (raw, stage, reason) in REJECTIONS {
+                let (got, _) = serve_once(raw, |mut s| {
+                    trace(evaluate(&mut s, &mut [0u8; REQUEST_BUF_BYTES]))
+                });
+                assert_eq!(got, (stage, reason));
+            }
+        }
+
+        // Unsigned requests stop at parse when requests must be signed.
+        #[cfg(not(feature = "corespec_signed_requests"))]
+        #[test]
+        fn magnitude_rejection_records_the_written_value() {
+            let raw = b"POST / HTTP/1.1\r\nContent-Length: 35\r\n\r\n{\"domain\":\"test\",\"magnitude\":0.000}";
+            let (outcome, _) = serve_once(raw, |mut s| {
+                match evaluate(&mut s, &mut [0u8; REQUEST_BUF_BYTES]) {
+                    Outcome::Rejected(rec) => Some(rec),
+                    _ => None,
+                }
+            });
+            let rec = outcome.unwrap();
+            assert_eq!(rec.reason, audit::Reason::MagnitudeRange);
+            assert_eq!(rec.domain_id, Some(0));
+            assert_eq!(
+                rec.magnitude,
+                Some(audit::Magnitude::Written(
+                    slime_core::parse_decimal("0.000").unwrap()
+                ))
+            );
+        }
+
+        /// `POST /action` with `body` as `content_type`.
+        fn post(content_type: &str, body: &[u8]) -> &'static [u8] {
+            let head = format!(
+                "POST /action HTTP/1.1\r\nContent-Type: {}\r\nContent-Length: {}\r\n\r\n",
+                content_type,
+                body.len()
+            );
+            [head.as_bytes(), body].concat().leak()
+        }
+
+        /// The same request as JSON and as CBOR.
+        const ENCODINGS: [(&str, &[u8]); 6] = [
+            (r#"{}"#, b"\xa0"),
+            (
+                r#"{"domain":"nope","magnitude":1}"#,
+                b"\xa2\x66domain\x64nope\x69magnitude\x01",
+            ),
+            (
+                r#"{"domain":"test","magnitude":0}"#,
+                b"\xa2\x66domain\x64test\x69magnitude\x00",
+            ),
+            (
+                r#"{"domain":"test","magnitude":20000}"#,
+                b"\xa2\x66domain\x64test\x69magnitude\x19\x4e\x20",
+            ),
+            (
+                r#"{"domain":"test","magnitude":1}"#,
+                b"\xa2\x66domain\x64test\x69magnitude\x01",
+            ),
+            // 0.5 is the decimal fraction 4([-1, 5]).
+            (
+                r#"{"parts":[{"domain":"test","magnitude":1},{"domain":"payment","magnitude":0.5}]}"#,
+                b"\xa1\x65parts\x82\xa2\x66domain\x64test\x69magnitude\x01\
+                  \xa2\x66domain\x67payment\x69magnitude\xc4\x82\x20\x05",
+            ),
+        ];
+
+        #[test]
+        fn json_and_cbor_bodies_get_identical_verdicts() {
+            crate::egress::connect_for_test();
+            for (json, cbor) in ENCODINGS {
+                let json = post("application/json", json.as_bytes());
+                let cbor = post("application/cbor", cbor);
+                let eval = |mut s: TcpStream| trace(evaluate(&mut s, &mut [0u8; REQUEST_BUF_BYTES]));
+                let (from_json, _) = serve_once(json, eval);
+                let (from_cbor, _) = serve_once(cbor, eval);
+                assert_eq!(from_cbor, from_json, "{:?}", String::from_utf8_lossy(json));
+                let ((), json_resp) = serve_once(json, handle);
+                let ((), cbor_resp) = serve_once(cbor, handle);
+                assert_eq!(cbor_resp, json_resp);
+            }
+        }
+
+        #[test]
+        fn cbor_body_sent_as_json_is_malformed() {
+            let (_, cbor) = ENCODINGS[4];
+            for content_type in ["application/json", "text/plain"] {
+                let (got, _) = serve_once(post(content_type, cbor), |mut s| {
+                    trace(evaluate(&mut s, &mut [0u8; REQUEST_BUF_BYTES]))
+                });
+                assert_eq!(got, (audit::Stage::Parse, audit::Reason::Malformed));
+            }
+        }
+
+        #[test]
+        fn payload_filling_the_body_cap_is_read_and_one_byte_more_is_not() {
+            // The body cap binds before the payload's 64 KB decoded limit.
+            let head = r#"{"domain":"test","magnitude":1,"payload":""#;
+            let room = MAX_BODY_BYTES - head.len() - r#""}"#.len();
+            let payload = "A".repeat(room / 4 * 4);
+            let body = |padding: usize| format!("{}{}\"{}}}", head, payload, " ".repeat(padding));
+            #[cfg(not(feature = "corespec_signed_requests"))]
+            let read = (audit::Stage::Law, audit::Reason::Authorized);
+            #[cfg(feature = "corespec_signed_requests")]
+            let read = (audit::Stage::Parse, audit::Reason::SignatureInvalid);
+
+            let eval = |mut s: TcpStream| trace(evaluate(&mut s, &mut [0u8; REQUEST_BUF_BYTES]));
+            let at_cap = body(room % 4);
+            assert_eq!(at_cap.len(), MAX_BODY_BYTES);
+            let (got, _) = serve_once(post("application/json", at_cap.as_bytes()), eval);
+            assert_eq!(got, read);
+            let over = body(room % 4 + 1);
+            let (got, _) = serve_once(post("application/json", over.as_bytes()), eval);
+            assert_eq!(got, (audit::Stage::Header, audit::Reason::HeaderRead));
+        }
+
+        #[test]
+        fn responses_are_byte_identical_across_internal_reasons() {
+            let expected = b"HTTP/1.1 200 OK\r\nContent-Length: 23\r\n\r\n{\"status\":\"IMPOSSIBLE\"}";
+            for (raw, _, _) in REJECTIONS {
+                let ((), resp) = serve_once(raw, handle);
+                assert_eq!(resp, expected);
+            }
+        }
+
+        // Unsigned requests stop at parse when requests must be signed.
+        #[cfg(not(feature = "corespec_signed_requests"))]
+        #[test]
+        fn counters_track_each_path_through_handle() {
+            use crate::observe;
+            use std::sync::atomic::{AtomicU64, Ordering};
+
+            // Other tests serve requests concurrently: assert growth, not equality.
+            let load = |c: &AtomicU64| c.load(Ordering::Relaxed);
+            for (raw, stage, _) in REJECTIONS {
+                let at = &observe::IMPOSSIBLE_AT[stage as usize];
+                let before = (load(&observe::REQUESTS), load(&observe::IMPOSSIBLE), load(at));
+                serve_once(raw, handle);
+                assert!(load(&observe::REQUESTS) > before.0);
+                assert!(load(&observe::IMPOSSIBLE) > before.1);
+                assert!(load(at) > before.2, "{:?}", stage);
+            }
+
+            crate::egress::connect_for_test();
+            let before = (load(&observe::REQUESTS), load(&observe::AUTHORIZED));
+            let ((), resp) = serve_once(
+                b"POST / HTTP/1.1\r\nContent-Length: 31\r\n\r\n{\"domain\":\"test\",\"magnitude\":1}",
+                handle,
+            );
+            assert!(resp.ends_with(AUTHORIZED_STATUS));
+            assert!(load(&observe::REQUESTS) > before.0);
+            assert!(load(&observe::AUTHORIZED) > before.1);
+        }
+
+        #[test]
+        fn request_path_does_not_allocate() {
+            use crate::failstop::{child_point, reexec, Point, INHERITED_FDS};
+            use std::os::fd::{AsRawFd, FromRawFd};
+            use std::path::PathBuf;
+
+            const NAME: &str = "ingress::tests::request_path_does_not_allocate";
+            let authorized: [&'static [u8]; 3] = [
+                b"POST / HTTP/1.1\r\nContent-Length: 31\r\n\r\n{\"domain\":\"test\",\"magnitude\":1}",
+                b"POST / HTTP/1.1\r\nContent-Type: application/cbor\r\nContent-Length: 24\r\n\r\n\
+                  \xa2\x66domain\x64test\x69magnitude\x01",
+                b"POST / HTTP/1.1\r\nContent-Length: 80\r\n\r\n{\"parts\":[{\"domain\":\"test\",\"magnitude\":1},{\"domain\":\"payment\",\"magnitude\":0.5}]}",
+            ];
+            let requests: Vec<&[u8]> = REJECTIONS.map(|(raw, _, _)| raw).into_iter().chain(authorized).collect();
+            // Named by the parent's pid, so the child finds the same file.
+            let audit_log = |pid: u32| -> PathBuf {
+                std::env::temp_dir().join(format!("slime-alloc-audit-{}.log", pid))
+            };
+
+            if child_point().is_some() {
+                // Boot as `main` does up to ingress, with egress and the
+                // audit log on files the parent holds.
+                crate::failstop::install();
+                crate::notify::init();
+                log::emit(log::Event::Boot, None);
+                // SAFETY: `reexec` placed the parent's egress socket here.
+                crate::egress::use_for_test(unsafe { UnixStream::from_raw_fd(INHERITED_FDS[0]) });
+                crate::audit::init_for_test(&audit_log(std::os::unix::process::parent_id()));
+                crate::shutdown::init_fail_closed();
+
+                // Every connection is accepted and queued before arming.
+                let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+                let mut clients = Vec::new();
+                let mut served = Vec::new();
+                for raw in &requests {
+                    let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+                    client.write_all(raw).unwrap();
+                    client.shutdown(std::net::Shutdown::Write).unwrap();
+                    let (stream, _) = listener.accept().unwrap();
+                    stream
+                        .set_blocking_with_timeout(Duration::from_secs(READ_TIMEOUT_SECS))
+                        .unwrap();
+                    clients.push(client);
+                    served.push(stream);
+                }
+
+                crate::alloc_guard::arm();
+                for stream in served {
+                    handle(stream);
+                }
+                let allocations = crate::alloc_guard::disarm();
+
+                eprintln!("allocations while serving: {}", allocations);
+                for mut client in clients {
+                    let mut resp = Vec::new();
+                    client.read_to_end(&mut resp).unwrap();
+                    assert!(resp.starts_with(b"HTTP/1.1 200 OK\r\n"));
+                }
+                assert_eq!(allocations, 0);
+                return;
+            }
+
+            let log_path = audit_log(std::process::id());
+            let _ = std::fs::remove_file(&log_path);
+            let (egress, mut actuator) = UnixStream::pair().unwrap();
+            let out = reexec(NAME, Point::Child, &[egress.as_raw_fd()]);
+            drop(egress);
+            let _ = actuator.read_to_end(&mut Vec::new());
+            let audit = std::fs::read_to_string(&log_path).unwrap_or_default();
+            let _ = std::fs::remove_file(&log_path);
+
+            let stderr = String::from_utf8_lossy(&out.stderr);
+            assert!(out.status.success(), "{}", stderr);
+            assert!(stderr.contains("allocations while serving: 0"), "{}", stderr);
+            // A boot record, then at least one decision per request.
+            assert!(audit.lines().count() > requests.len(), "{}", audit);
+        }
+
+        #[test]
+        fn panic_in_decision_path_exits_without_reply_or_partial_frame() {
+            use crate::failstop::{child_point, reexec, Point, INHERITED_FDS};
+            use std::os::fd::{AsRawFd, FromRawFd};
+
+            const NAME: &str =
+                "ingress::tests::panic_in_decision_path_exits_without_reply_or_partial_frame";
+            // Named by the parent's pid, so the child finds the same file.
+            let audit_log = |pid: u32| {
+                std::env::temp_dir().join(format!("slime-failstop-audit-{}.log", pid))
+            };
+            if let Some(point) = child_point() {
+                crate::failstop::install();
+                // SAFETY: `reexec` placed the parent's sockets at these fds.
+                let (client, egress) = unsafe {
+                    (
+                        TcpStream::from_raw_fd(INHERITED_FDS[0]),
+                        UnixStream::from_raw_fd(INHERITED_FDS[1]),
+                    )
+                };
+                crate::egress::use_for_test(egress);
+                crate::audit::init_for_test(&audit_log(std::os::unix::process::parent_id()));
+                crate::failstop::arm(point);
+                handle(client);
+                return;
+            }
+
+            // Two parts, so a torn write would show as a partial frame.
+            let parts = r#""parts":[{"domain":"test","magnitude":1},{"domain":"payment","magnitude":0.5}]"#;
+            #[cfg(not(feature = "corespec_signed_requests"))]
+            let body = format!("{{{}}}", parts);
+            #[cfg(feature = "corespec_signed_requests")]
+            let body = signed(parts, COMPOSITE_SIG);
+            let composite = format!("POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}", body.len(), body);
+            let composite = composite.as_bytes();
+            // A panic before egress still leaves the records on disk: they
+            // are written first.
+            let cases = [(Point::Decision, 0, 0), (Point::Egress, 2, 0), (Point::Reply, 2, 2)];
+            for (point, records, frames) in cases {
+                let _ = std::fs::remove_file(audit_log(std::process::id()));
+                let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+                let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+                let (served, _) = listener.accept().unwrap();
+                let (egress, mut actuator) = UnixStream::pair().unwrap();
+                client.write_all(composite).unwrap();
+                client.shutdown(std::net::Shutdown::Write).unwrap();
+
+                let out = reexec(NAME, point, &[served.as_raw_fd(), egress.as_raw_fd()]);
+                drop((served, egress));
+                let mut reply = Vec::new();
+                let _ = client.read_to_end(&mut reply);
+                let mut written = Vec::new();
+                let _ = actuator.read_to_end(&mut written);
+
+                let stderr = String::from_utf8_lossy(&out.stderr);
+                assert_eq!(out.status.code(), Some(1), "{:?}: {}", point, stderr);
+                assert!(stderr.contains("\"event\":\"panic\"}"), "{:?}: {}", point, stderr);
+                assert!(reply.is_empty(), "{:?}: reply {:?}", point, reply);
+                assert_eq!(written.len(), frames * FRAME_BYTES, "{:?}", point);
+                let audit = std::fs::read_to_string(audit_log(std::process::id())).unwrap();
+                let authorized = audit.matches("\"verdict\":\"AUTHORIZED\"").count();
+                assert_eq!(authorized, records, "{:?}: {}", point, audit);
+            }
+            let _ = std::fs::remove_file(audit_log(std::process::id()));
+        }
+
+        #[cfg(feature = "corespec_ingress_unix")]
+        #[test]
+        fn unadmitted_peer_gets_impossible_before_its_request_is_read() {
+            // The reference CoreSpec admits no one, so not this process either.
+            let request =
+                b"POST / HTTP/1.1\r\nContent-Length: 31\r\n\r\n{\"domain\":\"test\",\"magnitude\":1}";
+            let (served, mut client) = UnixStream::pair().unwrap();
+            client.write_all(request).unwrap();
+            let mut served_clone = served.try_clone().unwrap();
+            let outcome = trace(evaluate(&mut served_clone, &mut [0u8; REQUEST_BUF_BYTES]));
+            assert_eq!(outcome, (audit::Stage::Peer, audit::Reason::PeerCredentials));
+            // Nothing was consumed: the whole request is still queued.
+            let mut probe = [0u8; 4];
+            served.set_nonblocking(true).unwrap();
+            assert_eq!((&served).read(&mut probe).unwrap(), 4);
+
+            let (served, mut client) = UnixStream::pair().unwrap();
+            handle(served);
+            client.shutdown(std::net::Shutdown::Write).unwrap();
+            let mut resp = Vec::new();
+            client.read_to_end(&mut resp).unwrap();
+            assert_eq!(
+                resp,
+                b"HTTP/1.1 200 OK\r\nContent-Length: 23\r\n\r\n{\"status\":\"IMPOSSIBLE\"}"
+            );
+        }
+
+        #[cfg(feature = "corespec_ingress_tls")]
+        #[test]
+        fn refused_tls_peer_is_sent_nothing() {
+            let tcp = TcpListener::bind("127.0.0.1:0").unwrap();
+            let mut client = TcpStream::connect(tcp.local_addr().unwrap()).unwrap();
+            let listener = crate::tls::Listener::new(tcp, crate::tls::tests::refusing_config());
+            client
+                .write_all(b"POST / HTTP/1.1\r\nContent-Length: 2\r\n\r\n{}")
+                .unwrap();
+            client.shutdown(std::net::Shutdown::Write).unwrap();
+            let served = crate::accept::Listen::accept_one(&listener).unwrap();
+            served
+                .set_blocking_with_timeout(Duration::from_secs(READ_TIMEOUT_SECS))
+                .unwrap();
+            handle(served);
+            let mut resp = Vec::new();
+            client.read_to_end(&mut resp).unwrap();
+            assert!(resp.is_empty(), "reply {:?}", resp);
+        }
+
+        //
+        // -------------------- Signed requests --------------------
+        //
+        // Signatures by the RFC 8032 §7.1 TEST 1 key, which `SIGNING_KEYS`
+        // approves in tests, over the canonical bytes of each request.
+        //
+
+        #[cfg(feature = "corespec_signed_requests")]
+        const TEST_KEY: &str = "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a";
+        /// Over `{"domain":"test","magnitude":1}`, as in `slime_core::signature`.
+        #[cfg(feature = "corespec_signed_requests")]
+        const SINGLE_SIG: &str = "940e81751835cf25ccaecbac6fd1c83b331f1075650426f998851539ccaeefd1\
+                                  61a5bfc5e5016944e78bcbae21ea7234352ddf30996ecce8c86aa4f32a01910b";
+        /// Over the two-part request of the panic test.
+        #[cfg(feature = "corespec_signed_requests")]
+        const COMPOSITE_SIG: &str = "4520c2d83b5512cd67a11ee2af0761c84194f4887450c65330b3eb484e3751bd\
+                                     8676ec764d1c8fc7c508e8bc75b473fccaba78d921b697f6e16933ae3950cd02";
+
+        #[cfg(feature = "corespec_signed_requests")]
+        fn signed(fields: &str, sig: &str) -> String {
+            format!(r#"{{{},"signature":{{"key":"{}","sig":"{}"}}}}"#, fields, TEST_KEY, sig)
+        }
+
+        #[cfg(feature = "corespec_signed_requests")]
+        #[test]
+        fn unsigned_request_is_a_format_failure_before_domain_lookup() {
+            let raw = b"POST / HTTP/1.1\r\nContent-Length: 31\r\n\r\n{\"domain\":\"nope\",\"magnitude\":1}";
+            let (got, resp) = serve_once(raw, |mut s| {
+                trace(evaluate(&mut s, &mut [0u8; REQUEST_BUF_BYTES]))
+            });
+            assert_eq!(got, (audit::Stage::Parse, audit::Reason::SignatureInvalid));
+            assert!(resp.is_empty());
+            let ((), resp) = serve_once(raw, handle);
+            let text = String::from_utf8_lossy(&resp);
+            assert!(text.contains("{\"status\":\"IMPOSSIBLE\"}"));
+        }
+
+        #[cfg(feature = "corespec_signed_requests")]
+        #[test]
+        fn signed_request_is_authorized_through_handle() {
+            let body = signed(r#""domain":"test","magnitude":1"#, SINGLE_SIG);
+            let raw = format!("POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}", body.len(), body);
+            let raw: &'static [u8] = raw.into_bytes().leak();
+
+            crate::egress::connect_for_test();
+            let (got, _) = serve_once(raw, |mut s| {
+                trace(evaluate(&mut s, &mut [0u8; REQUEST_BUF_BYTES]))
+            });
+            assert_eq!(got, (audit::Stage::Law, audit::Reason::Authorized));
+            let ((), resp) = serve_once(raw, handle);
+            assert!(resp.ends_with(AUTHORIZED_STATUS), "{}", String::from_utf8_lossy(&resp));
+
+            // One byte of the signature changed: stopped at parse.
+            let tampered = String::from_utf8(raw.to_vec()).unwrap().replacen("940e", "940f", 1);
+            let (got, _) = serve_once(tampered.into_bytes().leak(), |mut s| {
+                trace(evaluate(&mut s, &mut [0u8; REQUEST_BUF_BYTES]))
+            });
+            assert_eq!(got, (audit::Stage::Parse, audit::Reason::SignatureInvalid));
+        }
+
+        #[test]
+        fn composite_with_unknown_part_returns_impossible() {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let addr = listener.local_addr().unwrap();
+
+            let t = std::thread::spawn(move || {
+                let (stream, _) = listener.accept().unwrap();
+                handle(stream);
+            });
+
+            let mut client = TcpStream::connect(addr).unwrap();
+            let body = br#"{"parts":[{"domain":"deploy","magnitude":1},{"domain":"nope","magnitude":1}]}"#;
+            let req = format!(
+                "POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(),
+                std::str::from_utf8(body).unwrap()
+            );
+            let _ = client.write_all(req.as_bytes());
+            let _ = client.shutdown(std::net::Shutdown::Write);
+
+            let mut resp = Vec::new();
+            let _ = client.read_to_end(&mut resp);
+            t.join().unwrap();
+
+            let text = String::from_utf8(resp).unwrap();
+            assert!(text.contains("{\"status\":\"IMPOSSIBLE\"}"));
+        }
     }
 }
 
@@ -431,8 +1121,26 @@ mod ingress {
 //
 
 fn main() {
+    crate::failstop::install();
+    crate::notify::init();
+    crate::log::emit(crate::log::Event::Boot, None);
+    crate::selfcheck::run_fail_closed();
     crate::egress::init_fail_closed();
+    crate::audit::init_fail_closed();
+    // The socket file must exist before Landlock; nobody connects until
+    // `start_local` listens.
+    #[cfg(feature = "corespec_ingress_unix")]
+    let ingress_socket = crate::local::bind_fail_closed();
+    // Every file the runner needs is open; threads spawned below inherit.
+    crate::sandbox::landlock_fail_closed();
+    crate::shutdown::init_fail_closed();
+    #[cfg(feature = "dashboard")]
+    crate::observe::init_fail_closed();
+    #[cfg(not(feature = "corespec_ingress_unix"))]
     ingress::start();
+    #[cfg(feature = "corespec_ingress_unix")]
+    ingress::start_local(ingress_socket);
+    crate::shutdown::finish();
 }
 
 #[cfg(test)]
@@ -448,7 +1156,9 @@ mod tests {
 
         let t = std::thread::spawn(move || {
             let (mut stream, _) = listener.accept().unwrap();
-            let body = read_http_body_hardened(&mut stream).unwrap();
+            let mut buf = [0u8; REQUEST_BUF_BYTES];
+            let (encoding, body) = read_http_body_hardened(&mut stream, &mut buf).unwrap();
+            assert_eq!(encoding, Encoding::Json);
             assert_eq!(body, br#"{"domain":"t","magnitude":1}"#);
         });
 
@@ -462,18 +1172,53 @@ mod tests {
     }
 
     #[test]
-    fn resolve_domain_known() {
-        assert!(resolve_domain("test").is_some());
-        assert!(resolve_domain("payment").is_some());
-        assert!(resolve_domain("deploy").is_some());
-        assert!(resolve_domain("db_prod").is_some());
+    fn read_http_body_hardened_reads_content_type() {
+        let cases: [(&'static [u8], Encoding); 4] = [
+            (b"Content-Type: application/cbor\r\n", Encoding::Cbor),
+            (b"content-type:  Application/CBOR; x=1\r\n", Encoding::Cbor),
+            (b"Content-Type: application/json\r\n", Encoding::Json),
+            (b"", Encoding::Json),
+        ];
+        for (header, expected) in cases {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            let addr = listener.local_addr().unwrap();
+
+            let t = std::thread::spawn(move || {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut buf = [0u8; REQUEST_BUF_BYTES];
+                let got = read_http_body_hardened(&mut stream, &mut buf);
+                assert_eq!(got, Some((expected, &b"\xa0"[..])));
+            });
+
+            let mut client = TcpStream::connect(addr).unwrap();
+            let req = [&b"POST /action HTTP/1.1\r\n"[..], header, b"Content-Length: 1\r\n\r\n\xa0"];
+            let _ = client.write_all(&req.concat());
+            let _ = client.shutdown(std::net::Shutdown::Write);
+
+            t.join().unwrap();
+        }
     }
 
     #[test]
-    fn resolve_domain_unknown() {
-        assert!(resolve_domain("unknown").is_none());
-        assert!(resolve_domain("").is_none());
-        assert!(resolve_domain("PAYMENT").is_none());
+    fn read_http_body_hardened_finds_terminator_split_across_reads() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let t = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; REQUEST_BUF_BYTES];
+            let body = read_http_body_hardened(&mut stream, &mut buf);
+            assert_eq!(body, Some((Encoding::Json, &b"{}"[..])));
+        });
+
+        let mut client = TcpStream::connect(addr).unwrap();
+        client.set_nodelay(true).unwrap();
+        let _ = client.write_all(b"POST / HTTP/1.1\r\nContent-Length: 2\r\n\r");
+        std::thread::sleep(Duration::from_millis(50));
+        let _ = client.write_all(b"\n{}");
+        let _ = client.shutdown(std::net::Shutdown::Write);
+
+        t.join().unwrap();
     }
 
     #[test]
@@ -483,7 +1228,8 @@ mod tests {
 
         let t = std::thread::spawn(move || {
             let (mut stream, _) = listener.accept().unwrap();
-            let body = read_http_body_hardened(&mut stream);
+            let mut buf = [0u8; REQUEST_BUF_BYTES];
+            let body = read_http_body_hardened(&mut stream, &mut buf);
             assert!(body.is_none());
         });
 
@@ -502,7 +1248,8 @@ mod tests {
 
         let t = std::thread::spawn(move || {
             let (mut stream, _) = listener.accept().unwrap();
-            let body = read_http_body_hardened(&mut stream);
+            let mut buf = [0u8; REQUEST_BUF_BYTES];
+            let body = read_http_body_hardened(&mut stream, &mut buf);
             assert!(body.is_none());
         });
 
@@ -521,7 +1268,8 @@ mod tests {
 
         let t = std::thread::spawn(move || {
             let (mut stream, _) = listener.accept().unwrap();
-            let body = read_http_body_hardened(&mut stream);
+            let mut buf = [0u8; REQUEST_BUF_BYTES];
+            let body = read_http_body_hardened(&mut stream, &mut buf);
             assert!(body.is_none());
         });
 
@@ -0,0 +1,152 @@
+//! systemd readiness protocol (`sd_notify`) — lifecycle signals only.
+//!
+//! - `READY=1`    once egress is connected and ingress is bound
+//! - `WATCHDOG=1` from the accept loop, every `WATCHDOG_INTERVAL_SECS`
+//! - `STOPPING=1` before the process exits
+//!
+//! `NOTIFY_SOCKET` is the only input, read once at boot: it says where to
+//! send these datagrams, never what to decide (V1 §2). Without it every call
+//! is a no-op. The heartbeat interval is compile-time; units using
+//! `WatchdogSec=` must allow at least twice this interval.
+
+use std::os::unix::net::{SocketAddr, UnixDatagram};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+pub const WATCHDOG_INTERVAL_SECS: u64 = 5;
+
+struct Target {
+    sock: UnixDatagram,
+    addr: SocketAddr,
+}
+
+static TARGET: OnceLock<Option<Target>> = OnceLock::new();
+
+impl Target {
+    /// `path` is a filesystem path, or `@name` for the abstract namespace.
+    fn open(path: &[u8]) -> Option<Self> {
+        use std::os::unix::ffi::OsStrExt;
+
+        let addr = match path.strip_prefix(b"@") {
+            Some(name) => {
+                use std::os::linux::net::SocketAddrExt;
+                SocketAddr::from_abstract_name(name).ok()?
+            }
+            None => SocketAddr::from_pathname(std::ffi::OsStr::from_bytes(path)).ok()?,
+        };
+        Some(Self {
+            sock: UnixDatagram::unbound().ok()?,
+            addr,
+        })
+    }
+
+    fn send(&self, msg: &[u8]) {
+        let _ = self.sock.send_to_addr(msg, &self.addr);
+    }
+}
+
+/// Read `NOTIFY_SOCKET`. Call first in `main`, so even a failing boot
+/// can report `STOPPING=1`.
+pub fn init() {
+    use std::os::unix::ffi::OsStrExt;
+
+    TARGET.get_or_init(|| {
+        let path = std::env::var_os("NOTIFY_SOCKET")?;
+        Target::open(path.as_bytes())
+    });
+}
+
+fn send(msg: &[u8]) {
+    if let Some(Some(target)) = TARGET.get() {
+        target.send(msg);
+    }
+}
+
+pub fn ready() {
+    send(b"READY=1");
+}
+
+pub fn stopping() {
+    send(b"STOPPING=1");
+}
+
+/// Rate-limits `WATCHDOG=1` so the accept loop can call `tick` every pass.
+pub struct Watchdog {
+    last: Instant,
+}
+
+impl Watchdog {
+    pub fn start() -> Self {
+        Self {
+            last: Instant::now(),
+        }
+    }
+
+    pub fn tick(&mut self) {
+        if self.last.elapsed() >= Duration::from_secs(WATCHDOG_INTERVAL_SECS) {
+            send(b"WATCHDOG=1");
+            self.last = Instant::now();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fake_notify_socket(name: &str) -> (UnixDatagram, std::path::PathBuf) {
+        let path = std::env::temp_dir().join(format!("slime-notify-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let sock = UnixDatagram::bind(&path).unwrap();
+        sock.set_read_timeout(Some(Duration::from_secs(2))).unwrap();
+        (sock, path)
+    }
+
+    fn recv(sock: &UnixDatagram) -> String {
+        let mut buf = [0u8; 64];
+        let n = sock.recv(&mut buf).unwrap();
+        String::from_utf8(buf[..n].to_vec()).unwrap()
+    }
+
+    #[test]
+    fn target_delivers_protocol_messages() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let (sock, path) = fake_notify_socket("path");
+        let target = Target::open(path.as_os_str().as_bytes()).unwrap();
+        target.send(b"READY=1");
+        target.send(b"WATCHDOG=1");
+        target.send(b"STOPPING=1");
+        assert_eq!(recv(&sock), "READY=1");
+        assert_eq!(recv(&sock), "WATCHDOG=1");
+        assert_eq!(recv(&sock), "STOPPING=1");
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn abstract_namespace_target() {
+        use std::os::linux::net::SocketAddrExt;
+
+        let name = format!("slime-notify-abstract-{}", std::process::id());
+        let addr = SocketAddr::from_abstract_name(name.as_bytes()).unwrap();
+        let sock = UnixDatagram::bind_addr(&addr).unwrap();
+        sock.set_read_timeout(Some(Duration::from_secs(2))).unwrap();
+
+        let target = Target::open(format!("@{}", name).as_bytes()).unwrap();
+        target.send(b"READY=1");
+        assert_eq!(recv(&sock), "READY=1");
+    }
+
+    #[test]
+    fn watchdog_is_rate_limited() {
+        let mut dog = Watchdog::start();
+        let before = dog.last;
+        dog.tick();
+        assert_eq!(dog.last, before);
+
+        dog.last = Instant::now() - Duration::from_secs(WATCHDOG_INTERVAL_SECS);
+        let stale = dog.last;
+        dog.tick();
+        assert!(dog.last > stale);
+    }
+}
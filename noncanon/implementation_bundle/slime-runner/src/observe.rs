@@ -0,0 +1,251 @@
+//! Observation port — read-only, off the decision path.
+//!
+//! Counters are plain atomics bumped by `ingress::handle` and `egress`;
+//! they are written there and only ever read here. With the `dashboard`
+//! feature the runner also serves, on `127.0.0.1:8081`:
+//!
+//! - `GET /`        — `resources/dashboard.html`
+//! - `GET /status`  — `{"uptime_s":..,"egress_connected":..,"requests":..,"authorized":..,"impossible":..}`
+//! - `GET /metrics` — the same counters in Prometheus text format
+//!
+//! The listener runs on its own thread and shares nothing with ingress but
+//! the atomics, so a slow or hostile observer cannot delay a verdict.
+
+use crate::audit::Stage;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+pub static REQUESTS: AtomicU64 = AtomicU64::new(0);
+pub static AUTHORIZED: AtomicU64 = AtomicU64::new(0);
+pub static IMPOSSIBLE: AtomicU64 = AtomicU64::new(0);
+/// IMPOSSIBLE verdicts by the stage that produced them, indexed by `Stage`.
+pub static IMPOSSIBLE_AT: [AtomicU64; Stage::ALL.len()] =
+    [const { AtomicU64::new(0) }; Stage::ALL.len()];
+pub static EGRESS_WRITE_FAILURES: AtomicU64 = AtomicU64::new(0);
+pub static EGRESS_RECONNECTS: AtomicU64 = AtomicU64::new(0);
+pub static EGRESS_CONNECTED: AtomicBool = AtomicBool::new(false);
+
+pub fn bump(counter: &AtomicU64) {
+    counter.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Count one IMPOSSIBLE verdict produced at `stage`.
+pub fn impossible_at(stage: Stage) {
+    bump(&IMPOSSIBLE);
+    bump(&IMPOSSIBLE_AT[stage as usize]);
+}
+
+#[cfg(feature = "dashboard")]
+pub use server::init_fail_closed;
+
+#[cfg(feature = "dashboard")]
+mod server {
+    use super::*;
+    use std::io::{Read, Write};
+    use crate::accept::Acceptor;
+    use crate::log;
+    use std::net::{TcpListener, TcpStream};
+    use std::sync::OnceLock;
+    use std::time::{Duration, Instant};
+
+    const ADDR: &str = "127.0.0.1:8081";
+    const DASHBOARD_HTML: &str = include_str!("../resources/dashboard.html");
+    const MAX_REQUEST_BYTES: usize = 1024;
+    const IO_TIMEOUT_SECS: u64 = 2;
+
+    static STARTED: OnceLock<Instant> = OnceLock::new();
+
+    /// Bind the observation port and serve it on a dedicated thread.
+    /// Exits before ingress bind if the port cannot be bound (V1 §7.2).
+    pub fn init_fail_closed() {
+        let listener = TcpListener::bind(ADDR)
+            .unwrap_or_else(|e| log::fatal(log::Event::ObserveBindFailed, e.raw_os_error()));
+        let _ = STARTED.set(Instant::now());
+        let acceptor = Acceptor::new(listener);
+        crate::sandbox::spawn_before_sandbox("observe", move || serve(acceptor))
+            .unwrap_or_else(|e| log::fatal(log::Event::ObserveBindFailed, e.raw_os_error()));
+    }
+
+    /// Serve until the listener breaks; the dashboard then goes dark
+    /// without touching ingress.
+    fn serve(mut acceptor: Acceptor<TcpListener>) {
+        while let Ok(next) = acceptor.next() {
+            if let Some(stream) = next {
+                handle(stream);
+            }
+        }
+    }
+
+    fn handle(mut stream: TcpStream) {
+        let timeout = Some(Duration::from_secs(IO_TIMEOUT_SECS));
+        let _ = stream.set_read_timeout(timeout);
+        let _ = stream.set_write_timeout(timeout);
+
+        let mut buf = [0u8; MAX_REQUEST_BYTES];
+        let n = stream.read(&mut buf).unwrap_or(0);
+        let (status, content_type, body) = route(&buf[..n]);
+        let header = format!(
+            "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            status,
+            content_type,
+            body.len()
+        );
+        let _ = stream.write_all(header.as_bytes());
+        let _ = stream.write_all(body.as_bytes());
+    }
+
+    /// Map a raw request to `(status line, content type, body)`.
+    /// Only `GET` is served; there is nothing to write to.
+    fn route(request: &[u8]) -> (&'static str, &'static str, String) {
+        let line = request.split(|&b| b == b'\r' || b == b'\n').next().unwrap_or(&[]);
+        match line {
+            b"GET / HTTP/1.1" | b"GET / HTTP/1.0" => {
+                ("200 OK", "text/html; charset=utf-8", DASHBOARD_HTML.to_string())
+            }
+            b"GET /status HTTP/1.1" | b"GET /status HTTP/1.0" => {
+                ("200 OK", "application/json", status_json())
+            }
+            b"GET /metrics HTTP/1.1" | b"GET /metrics HTTP/1.0" => {
+                ("200 OK", "text/plain; version=0.0.4", metrics_text())
+            }
+            _ => ("404 Not Found", "text/plain", String::new()),
+        }
+    }
+
+    fn status_json() -> String {
+        let uptime = STARTED.get().map(|t| t.elapsed().as_secs()).unwrap_or(0);
+        format!(
+            "{{\"uptime_s\":{},\"egress_connected\":{},\"requests\":{},\"authorized\":{},\"impossible\":{}}}",
+            uptime,
+            EGRESS_CONNECTED.load(Ordering::Relaxed),
+            REQUESTS.load(Ordering::Relaxed),
+            AUTHORIZED.load(Ordering::Relaxed),
+            IMPOSSIBLE.load(Ordering::Relaxed),
+        )
+    }
+
+    /// Prometheus text exposition (format 0.0.4).
+    fn metrics_text() -> String {
+        use std::fmt::Write;
+
+        let load = |c: &AtomicU64| c.load(Ordering::Relaxed);
+        let mut out = String::new();
+        let _ = write!(
+            out,
+            "# HELP slime_requests_total Connections accepted on ingress.\n\
+             # TYPE slime_requests_total counter\n\
+             slime_requests_total {}\n\
+             # HELP slime_verdicts_total Verdicts returned to clients.\n\
+             # TYPE slime_verdicts_total counter\n\
+             slime_verdicts_total{{verdict=\"AUTHORIZED\"}} {}\n\
+             slime_verdicts_total{{verdict=\"IMPOSSIBLE\"}} {}\n\
+             # HELP slime_impossible_total IMPOSSIBLE verdicts by the stage that produced them.\n\
+             # TYPE slime_impossible_total counter\n",
+            load(&REQUESTS),
+            load(&AUTHORIZED),
+            load(&IMPOSSIBLE),
+        );
+        for stage in Stage::ALL {
+            let _ = writeln!(
+                out,
+                "slime_impossible_total{{stage=\"{}\"}} {}",
+                stage.as_str(),
+                load(&IMPOSSIBLE_AT[stage as usize])
+            );
+        }
+        let _ = write!(
+            out,
+            "# HELP slime_egress_write_failures_total Egress frame writes that failed.\n\
+             # TYPE slime_egress_write_failures_total counter\n\
+             slime_egress_write_failures_total {}\n\
+             # HELP slime_egress_reconnects_total Egress reconnects after a failed write.\n\
+             # TYPE slime_egress_reconnects_total counter\n\
+             slime_egress_reconnects_total {}\n",
+            load(&EGRESS_WRITE_FAILURES),
+            load(&EGRESS_RECONNECTS),
+        );
+        out
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn serves_dashboard_and_status_only() {
+            let (status, ty, body) = route(b"GET / HTTP/1.1\r\nHost: x\r\n\r\n");
+            assert_eq!((status, ty), ("200 OK", "text/html; charset=utf-8"));
+            assert!(body.contains("Observation Dashboard"));
+
+            let (status, ty, body) = route(b"GET /status HTTP/1.1\r\n\r\n");
+            assert_eq!((status, ty), ("200 OK", "application/json"));
+            assert!(body.starts_with("{\"uptime_s\":"));
+            assert!(body.contains("\"egress_connected\":"));
+
+            for req in [
+                &b"POST /status HTTP/1.1\r\n\r\n"[..],
+                b"GET /../main.rs HTTP/1.1\r\n\r\n",
+                b"",
+            ] {
+                assert_eq!(route(req).0, "404 Not Found");
+            }
+        }
+
+        #[test]
+        fn metrics_expose_every_counter() {
+            let (status, ty, body) = route(b"GET /metrics HTTP/1.1\r\n\r\n");
+            assert_eq!((status, ty), ("200 OK", "text/plain; version=0.0.4"));
+            for name in [
+                "slime_requests_total ",
+                "slime_verdicts_total{verdict=\"AUTHORIZED\"} ",
+                "slime_verdicts_total{verdict=\"IMPOSSIBLE\"} ",
+                "slime_impossible_total{stage=\"peer\"} ",
+                "slime_impossible_total{stage=\"header\"} ",
+                "slime_impossible_total{stage=\"parse\"} ",
+                "slime_impossible_total{stage=\"domain\"} ",
+                "slime_impossible_total{stage=\"magnitude\"} ",
+                "slime_impossible_total{stage=\"law\"} ",
+                "slime_egress_write_failures_total ",
+                "slime_egress_reconnects_total ",
+            ] {
+                assert!(body.lines().any(|l| l.starts_with(name)), "{}", name);
+            }
+            // Every sample line is `name value` with an integer value.
+            for line in body.lines().filter(|l| !l.starts_with('#')) {
+                let value = line.rsplit(' ').next().unwrap();
+                assert!(value.parse::<u64>().is_ok(), "{}", line);
+            }
+        }
+
+        #[test]
+        fn status_reflects_counters() {
+            bump(&REQUESTS);
+            let body = status_json();
+            let requests: u64 = body
+                .split("\"requests\":")
+                .nth(1)
+                .and_then(|s| s.split(',').next())
+                .and_then(|s| s.parse().ok())
+                .unwrap();
+            assert!(requests >= 1);
+        }
+
+        #[test]
+        fn listener_answers_over_loopback() {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let addr = listener.local_addr().unwrap();
+            let t = std::thread::spawn(move || {
+                let (stream, _) = listener.accept().unwrap();
+                handle(stream);
+            });
+
+            let mut client = TcpStream::connect(addr).unwrap();
+            client.write_all(b"GET /status HTTP/1.1\r\n\r\n").unwrap();
+            let mut resp = String::new();
+            client.read_to_string(&mut resp).unwrap();
+            t.join().unwrap();
+
+            assert!(resp.starts_with("HTTP/1.1 200 OK\r\n"));
+            assert!(resp.contains("\r\n\r\n{\"uptime_s\":"));
+        }
+    }
+}
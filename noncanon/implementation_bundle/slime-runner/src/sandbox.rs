@@ -0,0 +1,403 @@
+//! Post-boot self-sandbox.
+//!
+//! Two layers, both fail-closed:
+//!
+//! 1. `landlock_fail_closed` — once egress is connected and the audit log is
+//!    open, the process drops every filesystem right (Landlock, ABI ≥ 3).
+//!    File descriptors already held keep working; nothing new can be opened,
+//!    created, truncated or executed. Applied before any thread is spawned,
+//!    so every later thread inherits it.
+//! 2. `seccomp_fail_closed` — see below.
+//!
+//! Once egress is connected and ingress is bound the runner only moves bytes
+//! between sockets it already holds. `seccomp_fail_closed` installs a
+//! seccomp-BPF allowlist for exactly that, on every thread (TSYNC); any
+//! other syscall — `openat`, `execve`, `ptrace`, … — kills the process.
+//!
+//! The allowlist is compile-time, per architecture. Egress reconnect keeps
+//! `socket`, for `AF_UNIX` only, and `connect`, which can then reach only
+//! a Unix socket; allocation keeps `brk`/`mmap`/`munmap`/`mremap`;
+//! the Unix-socket ingress keeps `getsockopt` for `SO_PEERCRED`; the TLS
+//! ingress keeps `getrandom` for handshake secrets; the audit sink keeps
+//! `ftruncate` to cut a failed write off the log it already holds.
+//! Thread startup (`rseq`, `set_robust_list`, `prctl` for the name, …) is
+//! not allowed, so every boot thread is started with `spawn_before_sandbox`.
+
+use crate::log;
+use libc::{c_long, sock_filter, sock_fprog};
+use std::sync::{Arc, Barrier};
+
+// <linux/audit.h>
+#[cfg(target_arch = "x86_64")]
+const AUDIT_ARCH: u32 = 0xC000_003E;
+#[cfg(target_arch = "aarch64")]
+const AUDIT_ARCH: u32 = 0xC000_00B7;
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+compile_error!("seccomp allowlist is defined for x86_64 and aarch64 only");
+
+/// `struct seccomp_data` offsets. `args[0]` is read as its low 32 bits
+/// (both targets are little-endian); `socket` takes an `int` domain.
+const DATA_NR: u32 = 0;
+const DATA_ARCH: u32 = 4;
+const DATA_ARG0: u32 = 16;
+
+/// Syscalls the runner makes after boot, on any thread, with any
+/// arguments. `socket` is checked separately.
+const ALLOWED: &[c_long] = &[
+    // sockets
+    libc::SYS_accept4,
+    libc::SYS_read,
+    libc::SYS_recvfrom,
+    libc::SYS_write,
+    libc::SYS_sendto,
+    libc::SYS_close,
+    libc::SYS_shutdown,
+    libc::SYS_setsockopt,
+    #[cfg(feature = "corespec_ingress_unix")]
+    libc::SYS_getsockopt,
+    #[cfg(feature = "corespec_ingress_tls")]
+    libc::SYS_getrandom,
+    libc::SYS_ioctl,
+    libc::SYS_fcntl,
+    libc::SYS_connect,
+    libc::SYS_ppoll,
+    #[cfg(target_arch = "x86_64")]
+    libc::SYS_poll,
+    // audit log: cut a failed write back to the last sealed line
+    libc::SYS_ftruncate,
+    // time and waiting
+    libc::SYS_clock_gettime,
+    libc::SYS_clock_nanosleep,
+    libc::SYS_nanosleep,
+    libc::SYS_futex,
+    libc::SYS_sched_yield,
+    libc::SYS_restart_syscall,
+    // memory
+    libc::SYS_brk,
+    libc::SYS_mmap,
+    libc::SYS_munmap,
+    libc::SYS_mremap,
+    libc::SYS_madvise,
+    libc::SYS_mprotect,
+    // signals and exit
+    libc::SYS_rt_sigreturn,
+    libc::SYS_rt_sigprocmask,
+    libc::SYS_sigaltstack,
+    libc::SYS_getpid,
+    libc::SYS_gettid,
+    libc::SYS_tgkill,
+    libc::SYS_exit,
+    libc::SYS_exit_group,
+];
+
+const FILTER_LEN: usize = ALLOWED.len() + 10;
+
+// Every allowed syscall jumps forward to the final ALLOW; `jt` is a u8.
+const _: () = assert!(ALLOWED.len() + 2 < 256);
+
+const fn stmt(code: u32, k: u32) -> sock_filter {
+    sock_filter {
+        code: code as u16,
+        jt: 0,
+        jf: 0,
+        k,
+    }
+}
+
+const fn jeq(k: u32, jt: u8, jf: u8) -> sock_filter {
+    sock_filter {
+        code: (libc::BPF_JMP | libc::BPF_JEQ | libc::BPF_K) as u16,
+        jt,
+        jf,
+        k,
+    }
+}
+
+/// ```text
+/// 0      ld  arch
+/// 1      jeq AUDIT_ARCH, +1
+/// 2      ret KILL_PROCESS
+/// 3      ld  nr
+/// 4      jeq socket, +0, +3
+/// 5      ld  args[0]
+/// 6      jeq AF_UNIX, → ALLOW
+/// 7      ret KILL_PROCESS
+/// 8+i    jeq ALLOWED[i], → ALLOW
+/// 8+n    ret KILL_PROCESS
+/// 9+n    ret ALLOW
+/// ```
+fn filter() -> [sock_filter; FILTER_LEN] {
+    let load = libc::BPF_LD | libc::BPF_W | libc::BPF_ABS;
+    let ret = libc::BPF_RET | libc::BPF_K;
+    let n = ALLOWED.len();
+
+    let mut f = [stmt(ret, libc::SECCOMP_RET_KILL_PROCESS); FILTER_LEN];
+    f[0] = stmt(load, DATA_ARCH);
+    f[1] = jeq(AUDIT_ARCH, 1, 0);
+    f[3] = stmt(load, DATA_NR);
+    f[4] = jeq(libc::SYS_socket as u32, 0, 3);
+    f[5] = stmt(load, DATA_ARG0);
+    f[6] = jeq(libc::AF_UNIX as u32, (n + 2) as u8, 0);
+    for (i, nr) in ALLOWED.iter().enumerate() {
+        f[8 + i] = jeq(*nr as u32, (n - i) as u8, 0);
+    }
+    f[9 + n] = stmt(ret, libc::SECCOMP_RET_ALLOW);
+    f
+}
+
+/// `no_new_privs`, then the filter on all threads. Allocation-free, so it
+/// is also safe between `fork` and `_exit` in tests.
+fn install(filter: &[sock_filter; FILTER_LEN]) -> Result<(), Option<i32>> {
+    let prog = sock_fprog {
+        len: FILTER_LEN as u16,
+        filter: filter.as_ptr() as *mut sock_filter,
+    };
+    // SAFETY: plain prctl/seccomp calls; `prog` and `filter` outlive them.
+    unsafe {
+        if libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) != 0 {
+            return Err(std::io::Error::last_os_error().raw_os_error());
+        }
+        let rc = libc::syscall(
+            libc::SYS_seccomp,
+            libc::SECCOMP_SET_MODE_FILTER,
+            libc::SECCOMP_FILTER_FLAG_TSYNC,
+            &prog as *const sock_fprog,
+        );
+        match rc {
+            0 => Ok(()),
+            // Positive: id of a thread that could not be synchronized.
+            r if r > 0 => Err(None),
+            _ => Err(std::io::Error::last_os_error().raw_os_error()),
+        }
+    }
+}
+
+// <linux/landlock.h>
+const LANDLOCK_CREATE_RULESET_VERSION: u32 = 1 << 0;
+/// Oldest ABI whose filesystem rights cover truncation.
+const LANDLOCK_ABI: libc::c_long = 3;
+/// Every `LANDLOCK_ACCESS_FS_*` right up to ABI 3, EXECUTE … TRUNCATE.
+const LANDLOCK_ACCESS_FS_ALL_V3: u64 = (1 << 15) - 1;
+
+#[repr(C)]
+struct LandlockRulesetAttr {
+    handled_access_fs: u64,
+}
+
+/// Supported Landlock ABI, or the errno explaining why there is none.
+fn landlock_abi() -> Result<libc::c_long, Option<i32>> {
+    // SAFETY: version query; no pointers are dereferenced.
+    let abi = unsafe {
+        libc::syscall(
+            libc::SYS_landlock_create_ruleset,
+            std::ptr::null::<LandlockRulesetAttr>(),
+            0usize,
+            LANDLOCK_CREATE_RULESET_VERSION,
+        )
+    };
+    if abi < 0 {
+        return Err(std::io::Error::last_os_error().raw_os_error());
+    }
+    Ok(abi)
+}
+
+/// Restrict the calling thread (and its future children) to no filesystem
+/// access at all: a ruleset handling every right, with no rules.
+/// Allocation-free.
+fn restrict_filesystem() -> Result<(), Option<i32>> {
+    let attr = LandlockRulesetAttr {
+        handled_access_fs: LANDLOCK_ACCESS_FS_ALL_V3,
+    };
+    // SAFETY: `attr` outlives the call; the ruleset fd is closed below.
+    unsafe {
+        let fd = libc::syscall(
+            libc::SYS_landlock_create_ruleset,
+            &attr as *const LandlockRulesetAttr,
+            std::mem::size_of::<LandlockRulesetAttr>(),
+            0u32,
+        );
+        if fd < 0 {
+            return Err(std::io::Error::last_os_error().raw_os_error());
+        }
+        let fd = fd as libc::c_int;
+        let rc = if libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) != 0 {
+            -1
+        } else {
+            libc::syscall(libc::SYS_landlock_restrict_self, fd, 0u32)
+        };
+        let err = std::io::Error::last_os_error().raw_os_error();
+        libc::close(fd);
+        if rc != 0 {
+            return Err(err);
+        }
+    }
+    Ok(())
+}
+
+/// Drop all filesystem access or exit. A kernel without Landlock ABI 3 is a
+/// missing OS capability (V1 §7.2). Call before spawning any thread.
+pub fn landlock_fail_closed() {
+    match landlock_abi() {
+        Ok(abi) if abi >= LANDLOCK_ABI => {}
+        Ok(_) => log::fatal(log::Event::LandlockUnavailable, None),
+        Err(errno) => log::fatal(log::Event::LandlockUnavailable, errno),
+    }
+    if let Err(errno) = restrict_filesystem() {
+        log::fatal(log::Event::SandboxFailed, errno);
+    }
+}
+
+/// Spawn a boot thread and return once it is running, so its startup
+/// syscalls cannot race the filter.
+pub fn spawn_before_sandbox<F>(name: &str, f: F) -> std::io::Result<()>
+where
+    F: FnOnce() + Send + 'static,
+{
+    let started = Arc::new(Barrier::new(2));
+    let in_thread = started.clone();
+    std::thread::Builder::new().name(name.into()).spawn(move || {
+        in_thread.wait();
+        f()
+    })?;
+    started.wait();
+    Ok(())
+}
+
+/// Install the allowlist or exit. Call last before the accept loop.
+pub fn seccomp_fail_closed() {
+    if let Err(errno) = install(&filter()) {
+        log::fatal(log::Event::SandboxFailed, errno);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fork, sandbox the child, run `after` in it; return its wait status.
+    fn run_sandboxed(after: fn()) -> libc::c_int {
+        let filter = filter();
+        // SAFETY: the child only makes raw syscalls and `_exit`s.
+        unsafe {
+            let pid = libc::fork();
+            assert!(pid >= 0);
+            if pid == 0 {
+                if install(&filter).is_err() {
+                    libc::_exit(99);
+                }
+                after();
+                libc::_exit(0);
+            }
+            let mut status = 0;
+            assert_eq!(libc::waitpid(pid, &mut status, 0), pid);
+            status
+        }
+    }
+
+    #[test]
+    fn openat_after_sandbox_kills_the_process() {
+        let status = run_sandboxed(|| unsafe {
+            libc::openat(libc::AT_FDCWD, c"/etc/hostname".as_ptr(), libc::O_RDONLY);
+        });
+        assert!(libc::WIFSIGNALED(status), "status {:#x}", status);
+        assert_eq!(libc::WTERMSIG(status), libc::SIGSYS);
+    }
+
+    #[test]
+    fn allowed_syscalls_keep_running() {
+        let status = run_sandboxed(|| unsafe {
+            libc::getpid();
+            libc::sched_yield();
+        });
+        assert!(libc::WIFEXITED(status), "status {:#x}", status);
+        assert_eq!(libc::WEXITSTATUS(status), 0);
+    }
+
+    #[test]
+    fn only_unix_sockets_can_be_created() {
+        use std::sync::atomic::{AtomicI32, Ordering};
+
+        let status = run_sandboxed(|| unsafe {
+            let fd = libc::socket(libc::AF_UNIX, libc::SOCK_STREAM | libc::SOCK_CLOEXEC, 0);
+            if fd < 0 {
+                libc::_exit(2);
+            }
+            libc::close(fd);
+        });
+        assert!(libc::WIFEXITED(status), "status {:#x}", status);
+        assert_eq!(libc::WEXITSTATUS(status), 0);
+
+        // `fn()` cannot capture; the forked child inherits the static.
+        static DOMAIN: AtomicI32 = AtomicI32::new(0);
+        for domain in [libc::AF_INET, libc::AF_INET6, libc::AF_NETLINK, libc::AF_PACKET] {
+            DOMAIN.store(domain, Ordering::SeqCst);
+            let status = run_sandboxed(|| unsafe {
+                libc::socket(DOMAIN.load(Ordering::SeqCst), libc::SOCK_STREAM, 0);
+            });
+            assert!(libc::WIFSIGNALED(status), "domain {}: status {:#x}", domain, status);
+            assert_eq!(libc::WTERMSIG(status), libc::SIGSYS);
+        }
+    }
+
+    #[test]
+    fn file_opens_fail_after_landlock() {
+        if !matches!(landlock_abi(), Ok(abi) if abi >= LANDLOCK_ABI) {
+            eprintln!("skipped: kernel lacks Landlock ABI {}", LANDLOCK_ABI);
+            return;
+        }
+        // SAFETY: the child only makes raw syscalls and `_exit`s.
+        let status = unsafe {
+            let pid = libc::fork();
+            assert!(pid >= 0);
+            if pid == 0 {
+                // Readable before, denied after.
+                let fd = libc::open(c"/proc/self/status".as_ptr(), libc::O_RDONLY);
+                if fd < 0 {
+                    libc::_exit(2);
+                }
+                libc::close(fd);
+                if restrict_filesystem().is_err() {
+                    libc::_exit(99);
+                }
+                let read = libc::open(c"/proc/self/status".as_ptr(), libc::O_RDONLY);
+                let read_errno = *libc::__errno_location();
+                let create = libc::open(
+                    c"/tmp/slime-landlock-probe".as_ptr(),
+                    libc::O_WRONLY | libc::O_CREAT,
+                    0o600,
+                );
+                let create_errno = *libc::__errno_location();
+                let denied = read < 0
+                    && read_errno == libc::EACCES
+                    && create < 0
+                    && create_errno == libc::EACCES;
+                libc::_exit(if denied { 0 } else { 1 });
+            }
+            let mut status = 0;
+            assert_eq!(libc::waitpid(pid, &mut status, 0), pid);
+            status
+        };
+        assert!(libc::WIFEXITED(status), "status {:#x}", status);
+        assert_eq!(libc::WEXITSTATUS(status), 0);
+    }
+
+    #[test]
+    fn filter_shape() {
+        let f = filter();
+        let ret = (libc::BPF_RET | libc::BPF_K) as u16;
+        assert_eq!(f[2].code, ret);
+        assert_eq!(f[2].k, libc::SECCOMP_RET_KILL_PROCESS);
+        assert_eq!(f[7].k, libc::SECCOMP_RET_KILL_PROCESS);
+        assert_eq!(f[FILTER_LEN - 2].k, libc::SECCOMP_RET_KILL_PROCESS);
+        assert_eq!(f[FILTER_LEN - 1].k, libc::SECCOMP_RET_ALLOW);
+        // Not `socket`: past its argument check, to the allowlist.
+        assert_eq!(4 + 1 + f[4].jf as usize, 8);
+        // Every allowing jump lands on the final ALLOW.
+        assert_eq!(6 + 1 + f[6].jt as usize, FILTER_LEN - 1);
+        for (i, ins) in f[8..8 + ALLOWED.len()].iter().enumerate() {
+            assert_eq!(8 + i + 1 + ins.jt as usize, FILTER_LEN - 1);
+        }
+        assert!(!ALLOWED.contains(&libc::SYS_socket));
+    }
+}
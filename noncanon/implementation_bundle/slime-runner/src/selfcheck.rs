@@ -0,0 +1,189 @@
+//! Boot self-check of the process's OS privileges.
+//!
+//! Runs first, before egress is touched. Each check either holds or the
+//! runner exits 1 with `selfcheck_failed` naming it (V1 §7.2: required OS
+//! capability missing):
+//!
+//! - `root`          — no real, effective or saved uid 0
+//! - `capabilities`  — empty effective and permitted sets. The kernel keeps
+//!   ambient ⊆ permitted, so this also rules out ambient capabilities. The
+//!   runner needs none: 8080 is unprivileged and egress is a plain connect.
+//! - `no_new_privs`  — set here, then read back
+//! - `core_dumps`    — `RLIMIT_CORE` 0 and not dumpable, set here, then read back
+//! - `memory_lock`   — `mlockall(MCL_CURRENT | MCL_FUTURE)` succeeds, so no
+//!   page is ever swapped out. Needs `LimitMEMLOCK=infinity` in the unit.
+
+use crate::log;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Check {
+    Root,
+    Capabilities,
+    NoNewPrivs,
+    CoreDumps,
+    MemoryLock,
+}
+
+impl Check {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Check::Root => "root",
+            Check::Capabilities => "capabilities",
+            Check::NoNewPrivs => "no_new_privs",
+            Check::CoreDumps => "core_dumps",
+            Check::MemoryLock => "memory_lock",
+        }
+    }
+}
+
+// <linux/capability.h>
+const LINUX_CAPABILITY_VERSION_3: u32 = 0x2008_0522;
+
+#[repr(C)]
+struct CapHeader {
+    version: u32,
+    pid: libc::c_int,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+struct CapData {
+    effective: u32,
+    permitted: u32,
+    inheritable: u32,
+}
+
+/// One check: `Err` carries the errno when an OS call failed.
+type Probe = fn() -> Result<(), Option<i32>>;
+
+fn last_errno() -> Option<i32> {
+    std::io::Error::last_os_error().raw_os_error()
+}
+
+fn not_root() -> Result<(), Option<i32>> {
+    let (mut r, mut e, mut s) = (0, 0, 0);
+    // SAFETY: the three out-pointers are valid for the call.
+    if unsafe { libc::getresuid(&mut r, &mut e, &mut s) } != 0 {
+        return Err(last_errno());
+    }
+    if r == 0 || e == 0 || s == 0 {
+        return Err(None);
+    }
+    Ok(())
+}
+
+fn no_capabilities() -> Result<(), Option<i32>> {
+    let mut header = CapHeader {
+        version: LINUX_CAPABILITY_VERSION_3,
+        pid: 0,
+    };
+    let mut data = [CapData::default(); 2];
+    // SAFETY: v3 takes one header and two data words; both outlive the call.
+    let rc = unsafe { libc::syscall(libc::SYS_capget, &mut header, data.as_mut_ptr()) };
+    if rc != 0 {
+        return Err(last_errno());
+    }
+    if data.iter().any(|d| d.effective != 0 || d.permitted != 0) {
+        return Err(None);
+    }
+    Ok(())
+}
+
+fn no_new_privs() -> Result<(), Option<i32>> {
+    // SAFETY: plain prctl calls without pointers.
+    unsafe {
+        if libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) != 0 {
+            return Err(last_errno());
+        }
+        if libc::prctl(libc::PR_GET_NO_NEW_PRIVS, 0, 0, 0, 0) != 1 {
+            return Err(None);
+        }
+    }
+    Ok(())
+}
+
+fn no_core_dumps() -> Result<(), Option<i32>> {
+    let zero = libc::rlimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
+    let mut now = libc::rlimit {
+        rlim_cur: 1,
+        rlim_max: 1,
+    };
+    // SAFETY: both rlimit pointers are valid for the calls.
+    unsafe {
+        if libc::setrlimit(libc::RLIMIT_CORE, &zero) != 0
+            || libc::prctl(libc::PR_SET_DUMPABLE, 0, 0, 0, 0) != 0
+            || libc::getrlimit(libc::RLIMIT_CORE, &mut now) != 0
+        {
+            return Err(last_errno());
+        }
+        let dumpable = libc::prctl(libc::PR_GET_DUMPABLE, 0, 0, 0, 0);
+        if now.rlim_cur != 0 || now.rlim_max != 0 || dumpable != 0 {
+            return Err(None);
+        }
+    }
+    Ok(())
+}
+
+fn memory_locked() -> Result<(), Option<i32>> {
+    // SAFETY: no pointers.
+    if unsafe { libc::mlockall(libc::MCL_CURRENT | libc::MCL_FUTURE) } != 0 {
+        return Err(last_errno());
+    }
+    Ok(())
+}
+
+/// Run every check in order; exit on the first that fails.
+pub fn run_fail_closed() {
+    let checks: [(Check, Probe); 5] = [
+        (Check::Root, not_root),
+        (Check::Capabilities, no_capabilities),
+        (Check::NoNewPrivs, no_new_privs),
+        (Check::CoreDumps, no_core_dumps),
+        (Check::MemoryLock, memory_locked),
+    ];
+    for (check, run) in checks {
+        if let Err(errno) = run() {
+            log::fatal(log::Event::SelfCheckFailed(check), errno);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fork, run `check` in the child, return whether it passed.
+    fn passes_in_child(check: Probe) -> bool {
+        // SAFETY: the child only makes raw syscalls and `_exit`s.
+        unsafe {
+            let pid = libc::fork();
+            assert!(pid >= 0);
+            if pid == 0 {
+                libc::_exit(if check().is_ok() { 0 } else { 1 });
+            }
+            let mut status = 0;
+            assert_eq!(libc::waitpid(pid, &mut status, 0), pid);
+            libc::WIFEXITED(status) && libc::WEXITSTATUS(status) == 0
+        }
+    }
+
+    #[test]
+    fn enforcing_checks_hold_after_they_run() {
+        assert!(passes_in_child(no_new_privs));
+        assert!(passes_in_child(no_core_dumps));
+    }
+
+    #[test]
+    fn root_and_capabilities_agree_with_the_test_process() {
+        // SAFETY: plain id query.
+        let root = unsafe { libc::geteuid() } == 0;
+        assert_eq!(passes_in_child(not_root), !root);
+        // Root holds capabilities unless the test runs in a stripped container.
+        if !root {
+            assert!(passes_in_child(no_capabilities));
+        }
+    }
+}
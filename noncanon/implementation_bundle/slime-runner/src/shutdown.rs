@@ -0,0 +1,49 @@
+//! Graceful shutdown on SIGTERM / SIGINT.
+//!
+//! A signal only raises a flag. The accept loop checks it between
+//! connections, so a request already inside `ingress::handle` runs to the
+//! end: an AUTHORIZED reply is never sent without its egress frame, and a
+//! frame is never written without its reply. Connections still queued in the
+//! backlog are dropped unanswered — nothing was decided for them.
+//!
+//! The drain is bounded: if the process is still alive `GRACE_SECS` after
+//! the signal (e.g. egress blocked under backpressure), it exits with code 1.
+
+use crate::log;
+use signal_hook::consts::{SIGINT, SIGTERM};
+use signal_hook::iterator::Signals;
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+pub const GRACE_SECS: u64 = 5;
+
+static REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// Install the handlers. Exits if they cannot be installed: a runner that
+/// cannot be stopped cleanly is a partial initialization (V1 §7.2).
+pub fn init_fail_closed() {
+    let mut signals = Signals::new([SIGTERM, SIGINT])
+        .unwrap_or_else(|e| log::fatal(log::Event::SignalSetupFailed, e.raw_os_error()));
+    crate::sandbox::spawn_before_sandbox("shutdown", move || {
+        if signals.forever().next().is_some() {
+            REQUESTED.store(true, Ordering::SeqCst);
+            log::emit(log::Event::ShutdownRequested, None);
+            crate::notify::stopping();
+            std::thread::sleep(Duration::from_secs(GRACE_SECS));
+            log::fatal(log::Event::ShutdownGraceExpired, None);
+        }
+    })
+    .unwrap_or_else(|e| log::fatal(log::Event::SignalSetupFailed, e.raw_os_error()));
+}
+
+pub fn requested() -> bool {
+    REQUESTED.load(Ordering::SeqCst)
+}
+
+/// Called once ingress has stopped: flush egress and exit 0.
+pub fn finish() -> ! {
+    crate::egress::flush();
+    log::emit(log::Event::Exit(0), None);
+    process::exit(0)
+}
@@ -0,0 +1,434 @@
+//! TLS ingress (`corespec_ingress_tls`).
+//!
+//! The same HTTP ingress on `127.0.0.1:8080`, inside TLS 1.3 terminated by
+//! the runner itself, so no reverse proxy sits in front of it:
+//!
+//! - The server chain and key come from the CoreSpec, embedded at build
+//!   time. Nothing is read from disk; an empty or unusable certificate
+//!   stops boot (`tls_config_invalid`) before ingress binds (V1 §7.2).
+//! - Client authentication is mandatory. The client presents its
+//!   certificate followed by its chain up to and including the issuing CA;
+//!   that CA's SHA-256 must be in `TLS_CLIENT_CA_SHA256`, and the chain
+//!   must verify under it alone. No pin, no trust store.
+//! - The handshake completes in `ingress::evaluate` before a byte of HTTP
+//!   is parsed, within `HANDSHAKE_TIMEOUT` in total however the client
+//!   paces its bytes. A refused, broken or late handshake is IMPOSSIBLE at
+//!   stage `peer`; the verdict cannot reach a client that has no session,
+//!   so nothing is written back and it sees the connection close.
+
+use crate::log;
+use rustls::client::danger::HandshakeSignatureValid;
+use rustls::crypto::{verify_tls12_signature, verify_tls13_signature, CryptoProvider};
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer, UnixTime};
+use rustls::server::danger::{ClientCertVerified, ClientCertVerifier};
+use rustls::server::WebPkiClientVerifier;
+use rustls::{
+    CertificateError, DigitallySignedStruct, DistinguishedName, RootCertStore, ServerConfig,
+    ServerConnection, SignatureScheme, StreamOwned,
+};
+use slime_core::corespec::{TLS_CLIENT_CA_SHA256, TLS_SERVER_CERT_CHAIN, TLS_SERVER_KEY};
+use std::io;
+use std::net::{TcpListener, TcpStream};
+use std::os::fd::{AsFd, BorrowedFd};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Time a client has to complete the handshake, start to finish.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// An accepted connection, handshake not yet done.
+pub type Stream = StreamOwned<ServerConnection, TcpStream>;
+
+/// Client CAs by certificate fingerprint.
+#[derive(Debug)]
+struct PinnedCa {
+    pins: Vec<[u8; 32]>,
+    provider: Arc<CryptoProvider>,
+}
+
+impl PinnedCa {
+    fn pinned(&self, cert: &CertificateDer<'_>) -> bool {
+        let digest = ring::digest::digest(&ring::digest::SHA256, cert);
+        self.pins.iter().any(|pin| pin[..] == *digest.as_ref())
+    }
+}
+
+impl ClientCertVerifier for PinnedCa {
+    fn root_hint_subjects(&self) -> &[DistinguishedName] {
+        &[]
+    }
+
+    /// Accept if the chain verifies with one presented, pinned CA as the
+    /// only trust anchor.
+    fn verify_client_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        now: UnixTime,
+    ) -> Result<ClientCertVerified, rustls::Error> {
+        for ca in intermediates.iter().filter(|cert| self.pinned(cert)) {
+            let mut roots = RootCertStore::empty();
+            if roots.add(ca.clone().into_owned()).is_err() {
+                continue;
+            }
+            let provider = Arc::clone(&self.provider);
+            let Ok(verifier) =
+                WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider).build()
+            else {
+                continue;
+            };
+            if let Ok(verified) = verifier.verify_client_cert(end_entity, intermediates, now) {
+                return Ok(verified);
+            }
+        }
+        Err(rustls::Error::InvalidCertificate(
+            CertificateError::UnknownIssuer,
+        ))
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        let algorithms = &self.provider.signature_verification_algorithms;
+        verify_tls12_signature(message, cert, dss, algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        let algorithms = &self.provider.signature_verification_algorithms;
+        verify_tls13_signature(message, cert, dss, algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.provider
+            .signature_verification_algorithms
+            .supported_schemes()
+    }
+}
+
+/// TLS 1.3 only, client certificate required and checked against `pins`.
+fn server_config(
+    chain: &[&[u8]],
+    key: &[u8],
+    pins: &[[u8; 32]],
+) -> Result<ServerConfig, rustls::Error> {
+    if chain.is_empty() || key.is_empty() {
+        return Err(rustls::Error::General("no server certificate".into()));
+    }
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let verifier = Arc::new(PinnedCa {
+        pins: pins.to_vec(),
+        provider: Arc::clone(&provider),
+    });
+    let chain = chain
+        .iter()
+        .map(|der| CertificateDer::from(der.to_vec()))
+        .collect();
+    let key = PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(key.to_vec()));
+    ServerConfig::builder_with_provider(provider)
+        .with_protocol_versions(&[&rustls::version::TLS13])?
+        .with_client_cert_verifier(verifier)
+        .with_single_cert(chain, key)
+}
+
+/// The CoreSpec server configuration; exits if it is unusable.
+pub fn config_fail_closed() -> Arc<ServerConfig> {
+    let config = server_config(TLS_SERVER_CERT_CHAIN, TLS_SERVER_KEY, TLS_CLIENT_CA_SHA256)
+        .map(Arc::new)
+        .unwrap_or_else(|_| log::fatal(log::Event::TlsConfigInvalid, None));
+    // Proves `Listener::accept_one` cannot fail on the session itself.
+    if ServerConnection::new(Arc::clone(&config)).is_err() {
+        log::fatal(log::Event::TlsConfigInvalid, None);
+    }
+    config
+}
+
+/// The ingress TCP listener, handing out TLS sessions.
+pub struct Listener {
+    tcp: TcpListener,
+    config: Arc<ServerConfig>,
+}
+
+impl Listener {
+    pub fn new(tcp: TcpListener, config: Arc<ServerConfig>) -> Self {
+        Self { tcp, config }
+    }
+}
+
+impl AsFd for Listener {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.tcp.as_fd()
+    }
+}
+
+impl crate::accept::Listen for Listener {
+    type Stream = Stream;
+    fn accept_one(&self) -> io::Result<Stream> {
+        let (sock, _) = self.tcp.accept()?;
+        let session = ServerConnection::new(Arc::clone(&self.config)).map_err(io::Error::other)?;
+        Ok(StreamOwned::new(session, sock))
+    }
+}
+
+/// Complete the handshake before `HANDSHAKE_TIMEOUT`. False if it fails
+/// or runs late, which includes every client without a certificate from a
+/// pinned CA. One record read at a time (not `complete_io`, which keeps
+/// reading while bytes trickle in), none waiting past the deadline; the
+/// socket's own read timeout is restored for the request.
+pub fn handshake(stream: &mut Stream) -> bool {
+    let Ok(per_read) = stream.sock.read_timeout() else {
+        return false;
+    };
+    let deadline = Instant::now() + HANDSHAKE_TIMEOUT;
+    while stream.conn.is_handshaking() {
+        if stream.conn.wants_write() {
+            if stream.conn.write_tls(&mut stream.sock).is_err() {
+                return false;
+            }
+            continue;
+        }
+        let left = deadline.saturating_duration_since(Instant::now());
+        let wait = per_read.map_or(left, |t| t.min(left));
+        if wait.is_zero() || stream.sock.set_read_timeout(Some(wait)).is_err() {
+            return false;
+        }
+        match stream.conn.read_tls(&mut stream.sock) {
+            Ok(0) => return false,
+            Ok(_) => {}
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(_) => return false,
+        }
+        if stream.conn.process_new_packets().is_err() {
+            return false;
+        }
+    }
+    stream.sock.set_read_timeout(per_read).is_ok() && stream.conn.peer_certificates().is_some()
+}
+
+/// Send `close_notify` after the reply. Best effort, never reads.
+pub fn close(stream: &mut Stream) {
+    stream.conn.send_close_notify();
+    while stream.conn.wants_write() {
+        if stream.conn.write_tls(&mut stream.sock).is_err() {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::accept::Listen;
+    use rcgen::{BasicConstraints, CertificateParams, CertifiedIssuer, DnType, IsCa, KeyPair};
+    use rustls::{ClientConfig, ClientConnection};
+    use std::io::{Read, Write};
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    type Issuer = CertifiedIssuer<'static, KeyPair>;
+    /// A chain, leaf first, and the leaf's PKCS#8 key.
+    type Identity = (Vec<CertificateDer<'static>>, Vec<u8>);
+
+    fn ca(name: &str) -> Issuer {
+        let mut params = CertificateParams::new(Vec::<String>::new()).unwrap();
+        params.distinguished_name.push(DnType::CommonName, name);
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        CertifiedIssuer::self_signed(params, KeyPair::generate().unwrap()).unwrap()
+    }
+
+    /// A leaf for `name` issued by `issuer`, sent with the issuer.
+    fn identity(name: &str, issuer: &Issuer) -> Identity {
+        let key = KeyPair::generate().unwrap();
+        let params = CertificateParams::new(vec![name.to_string()]).unwrap();
+        let cert = params.signed_by(&key, issuer).unwrap();
+        (
+            vec![cert.der().clone(), issuer.der().clone()],
+            key.serialize_der(),
+        )
+    }
+
+    fn pin(issuer: &Issuer) -> [u8; 32] {
+        let digest = ring::digest::digest(&ring::digest::SHA256, issuer.der());
+        digest.as_ref().try_into().unwrap()
+    }
+
+    struct Pki {
+        server_ca: Issuer,
+        server: Identity,
+        client_ca: Issuer,
+    }
+
+    fn pki() -> Pki {
+        let server_ca = ca("slime test server CA");
+        let server = identity("localhost", &server_ca);
+        Pki {
+            server_ca,
+            server,
+            client_ca: ca("slime test client CA"),
+        }
+    }
+
+    fn config(pki: &Pki, pins: &[[u8; 32]]) -> Arc<ServerConfig> {
+        // The server sends its leaf only, as a deployment would embed it.
+        let chain = [pki.server.0[0].as_ref()];
+        Arc::new(server_config(&chain, &pki.server.1, pins).unwrap())
+    }
+
+    /// A usable server configuration that pins no client CA.
+    pub(crate) fn refusing_config() -> Arc<ServerConfig> {
+        config(&pki(), &[])
+    }
+
+    fn client_config(pki: &Pki, identity: Option<Identity>) -> Arc<ClientConfig> {
+        let mut roots = RootCertStore::empty();
+        roots.add(pki.server_ca.der().clone()).unwrap();
+        let builder =
+            ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+                .with_protocol_versions(&[&rustls::version::TLS13])
+                .unwrap()
+                .with_root_certificates(roots);
+        let config = match identity {
+            Some((chain, key)) => builder
+                .with_client_auth_cert(chain, PrivateKeyDer::Pkcs8(key.into()))
+                .unwrap(),
+            None => builder.with_no_client_auth(),
+        };
+        Arc::new(config)
+    }
+
+    /// Serve one connection from `client` on a `Listener`; `Some(bytes)`
+    /// read after the handshake if the server admitted it.
+    fn serve_one(
+        config: Arc<ServerConfig>,
+        client: impl FnOnce(TcpStream) + Send + 'static,
+    ) -> Option<Vec<u8>> {
+        let tcp = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = tcp.local_addr().unwrap();
+        let t = std::thread::spawn(move || {
+            let sock = TcpStream::connect(addr).unwrap();
+            sock.set_read_timeout(Some(TIMEOUT)).unwrap();
+            client(sock);
+        });
+        let mut stream = Listener::new(tcp, config).accept_one().unwrap();
+        stream.sock.set_read_timeout(Some(TIMEOUT)).unwrap();
+        let admitted = handshake(&mut stream);
+        let mut got = [0u8; 4];
+        let result = admitted.then(|| {
+            stream.read_exact(&mut got).unwrap();
+            close(&mut stream);
+            got.to_vec()
+        });
+        drop(stream);
+        t.join().unwrap();
+        result
+    }
+
+    /// A TLS client that sends `ping` and waits for the server to close.
+    fn tls_client(config: Arc<ClientConfig>) -> impl FnOnce(TcpStream) + Send + 'static {
+        move |sock| {
+            let session = ClientConnection::new(config, "localhost".try_into().unwrap()).unwrap();
+            let mut tls = StreamOwned::new(session, sock);
+            if tls.write_all(b"ping").is_ok() {
+                // Held open until the server has decided.
+                let _ = tls.read_to_end(&mut Vec::new());
+            }
+        }
+    }
+
+    #[test]
+    fn client_from_pinned_ca_is_admitted() {
+        let pki = pki();
+        let client = client_config(&pki, Some(identity("agent", &pki.client_ca)));
+        let got = serve_one(config(&pki, &[pin(&pki.client_ca)]), tls_client(client));
+        assert_eq!(got.as_deref(), Some(&b"ping"[..]));
+    }
+
+    #[test]
+    fn client_from_unpinned_ca_is_refused() {
+        let pki = pki();
+        let other = ca("someone else's CA");
+        let client = client_config(&pki, Some(identity("agent", &other)));
+        assert_eq!(
+            serve_one(config(&pki, &[pin(&pki.client_ca)]), tls_client(client)),
+            None
+        );
+    }
+
+    #[test]
+    fn pinned_ca_must_have_issued_the_leaf() {
+        // The pinned CA is sent, but another CA signed the leaf.
+        let pki = pki();
+        let other = ca("someone else's CA");
+        let (mut chain, key) = identity("agent", &other);
+        chain[1] = pki.client_ca.der().clone();
+        let client = client_config(&pki, Some((chain, key)));
+        assert_eq!(
+            serve_one(config(&pki, &[pin(&pki.client_ca)]), tls_client(client)),
+            None
+        );
+    }
+
+    #[test]
+    fn client_without_certificate_is_refused() {
+        let pki = pki();
+        let client = client_config(&pki, None);
+        assert_eq!(
+            serve_one(config(&pki, &[pin(&pki.client_ca)]), tls_client(client)),
+            None
+        );
+    }
+
+    #[test]
+    fn plaintext_http_is_refused_before_parsing() {
+        let pki = pki();
+        let got = serve_one(config(&pki, &[pin(&pki.client_ca)]), |mut sock| {
+            let _ = sock.write_all(b"POST / HTTP/1.1\r\nContent-Length: 2\r\n\r\n{}");
+            let mut reply = Vec::new();
+            let _ = sock.read_to_end(&mut reply);
+            assert!(!reply.windows(4).any(|w| w == b"HTTP"), "plaintext reply");
+        });
+        assert_eq!(got, None);
+    }
+
+    #[test]
+    fn stalled_handshake_is_cut_at_the_deadline() {
+        let pki = pki();
+        let client = client_config(&pki, Some(identity("agent", &pki.client_ca)));
+        let started = Instant::now();
+        let got = serve_one(config(&pki, &[pin(&pki.client_ca)]), move |mut sock| {
+            let mut session = ClientConnection::new(client, "localhost".try_into().unwrap()).unwrap();
+            let mut hello = Vec::new();
+            session.write_tls(&mut hello).unwrap();
+            // Every byte well inside the per-read timeout, the whole far past
+            // the deadline.
+            for byte in hello {
+                if sock.write_all(&[byte]).is_err() {
+                    return;
+                }
+                std::thread::sleep(Duration::from_millis(100));
+            }
+        });
+        assert_eq!(got, None);
+        assert!(started.elapsed() < HANDSHAKE_TIMEOUT + Duration::from_secs(2));
+    }
+
+    #[test]
+    fn no_pins_admit_no_one() {
+        let pki = pki();
+        let client = client_config(&pki, Some(identity("agent", &pki.client_ca)));
+        assert_eq!(serve_one(config(&pki, &[]), tls_client(client)), None);
+    }
+
+    #[test]
+    fn reference_corespec_has_no_usable_certificate() {
+        let config = server_config(TLS_SERVER_CERT_CHAIN, TLS_SERVER_KEY, TLS_CLIENT_CA_SHA256);
+        assert!(config.is_err());
+    }
+}
@@ -0,0 +1,44 @@
+// Boot event log — fail-closed boot is explained on stderr
+//
+// Proves: when the egress socket is absent the runner exits 1 and leaves one
+// JSON object per line on stderr: `boot`, `egress_connect_failed` with the OS
+// errno, then `exit` with the code. Nothing is written to stdout.
+//
+// Ignored by default: needs LimitMEMLOCK=infinity (see `common/mod.rs`).
+//
+// V1_INVARIANTS ref: §7.2 (Fail-Closed)
+// EGRESS_SOCKET_SPEC ref: §Monitoring (structured log lines)
+
+mod common;
+
+use std::process::Stdio;
+
+const ENOENT: i32 = 2;
+
+#[test]
+#[ignore = "needs LimitMEMLOCK=infinity"]
+fn failing_boot_logs_reason_and_exit() {
+    common::assert_no_egress();
+    let mut runner = common::runner();
+    let out = runner
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+
+    assert_eq!(out.status.code(), Some(1));
+    assert!(out.stdout.is_empty());
+
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    let lines: Vec<&str> = stderr.lines().collect();
+    assert_eq!(lines.len(), 3, "{}", stderr);
+    for line in &lines {
+        assert!(line.starts_with("{\"ts\":\"") && line.ends_with('}'), "{}", line);
+        assert!(line.contains("Z\",\"level\":\""), "{}", line);
+    }
+    assert!(lines[0].ends_with("\"level\":\"INFO\",\"event\":\"boot\"}"));
+    assert!(lines[1].ends_with(&format!(
+        "\"level\":\"ERROR\",\"event\":\"egress_connect_failed\",\"errno\":{}}}",
+        ENOENT
+    )));
+    assert!(lines[2].ends_with("\"level\":\"ERROR\",\"event\":\"exit\",\"code\":1}"));
+}
@@ -0,0 +1,134 @@
+// Boot self-check — the runner refuses an over-privileged or unlockable process
+//
+// Proves: before egress is touched, the runner exits 1 with
+// `selfcheck_failed` naming the check when it runs as root, when it holds a
+// capability (ambient, here), or when its memory cannot be locked. The
+// passing path is `boot_log.rs`, which boots unprivileged and reaches egress.
+//
+// The root and capability cases need a root test process; they skip
+// otherwise. The memory-lock case is ignored by default: it needs
+// LimitMEMLOCK=infinity (see `common/mod.rs`).
+//
+// V1_INVARIANTS ref: §7.2 (Fail-Closed — required OS capability missing)
+
+mod common;
+
+use std::os::unix::process::CommandExt;
+use std::process::{Command, Output, Stdio};
+
+// <linux/capability.h>
+const LINUX_CAPABILITY_VERSION_3: u32 = 0x2008_0522;
+const CAP_NET_BIND_SERVICE: u32 = 10;
+
+/// Exit 1 after exactly `boot`, `selfcheck_failed` for `check`, `exit`.
+fn assert_refused(out: Output, check: &str) {
+    assert_eq!(out.status.code(), Some(1));
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    let lines: Vec<&str> = stderr.lines().collect();
+    assert_eq!(lines.len(), 3, "{}", stderr);
+    assert!(lines[0].ends_with("\"event\":\"boot\"}"), "{}", stderr);
+    assert!(lines[1].contains("\"level\":\"ERROR\",\"event\":\"selfcheck_failed\""), "{}", stderr);
+    assert!(lines[1].ends_with(&format!("\"check\":\"{}\"}}", check)), "{}", stderr);
+    assert!(lines[2].ends_with("\"event\":\"exit\",\"code\":1}"), "{}", stderr);
+}
+
+#[test]
+fn root_is_refused() {
+    if !common::is_root() {
+        eprintln!("skipped: needs root");
+        return;
+    }
+    let out = Command::new(common::BIN)
+        .env_clear()
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert_refused(out, "root");
+}
+
+#[test]
+fn ambient_capability_is_refused() {
+    if !common::is_root() || !common::executable_by_nobody() {
+        eprintln!("skipped: needs root and a runner binary executable by nobody");
+        return;
+    }
+    let mut cmd = Command::new(common::BIN);
+    cmd.env_clear().stdin(Stdio::null());
+    // SAFETY: only raw syscalls between fork and exec.
+    unsafe {
+        cmd.pre_exec(|| {
+            #[repr(C)]
+            struct Header {
+                version: u32,
+                pid: libc::c_int,
+            }
+            #[repr(C)]
+            struct Data {
+                effective: u32,
+                permitted: u32,
+                inheritable: u32,
+            }
+
+            let fail = || Err(std::io::Error::last_os_error());
+            // Become `nobody` but keep permitted capabilities across setuid.
+            if libc::prctl(libc::PR_SET_KEEPCAPS, 1, 0, 0, 0) != 0
+                || libc::setgroups(0, std::ptr::null()) != 0
+                || libc::setgid(common::NOBODY) != 0
+                || libc::setuid(common::NOBODY) != 0
+            {
+                return fail();
+            }
+            let bit = 1 << CAP_NET_BIND_SERVICE;
+            let header = Header {
+                version: LINUX_CAPABILITY_VERSION_3,
+                pid: 0,
+            };
+            let data = [
+                Data {
+                    effective: bit,
+                    permitted: bit,
+                    inheritable: bit,
+                },
+                Data {
+                    effective: 0,
+                    permitted: 0,
+                    inheritable: 0,
+                },
+            ];
+            if libc::syscall(libc::SYS_capset, &header, data.as_ptr()) != 0
+                || libc::prctl(
+                    libc::PR_CAP_AMBIENT,
+                    libc::PR_CAP_AMBIENT_RAISE,
+                    CAP_NET_BIND_SERVICE as libc::c_ulong,
+                    0,
+                    0,
+                ) != 0
+            {
+                return fail();
+            }
+            Ok(())
+        });
+    }
+    assert_refused(cmd.output().unwrap(), "capabilities");
+}
+
+#[test]
+#[ignore = "needs LimitMEMLOCK=infinity"]
+fn unlockable_memory_is_refused() {
+    let mut runner = common::runner();
+    // SAFETY: only a raw syscall between fork and exec.
+    unsafe {
+        runner.pre_exec(|| {
+            let none = libc::rlimit {
+                rlim_cur: 0,
+                rlim_max: 0,
+            };
+            if libc::setrlimit(libc::RLIMIT_MEMLOCK, &none) != 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+    let out = runner.stdin(Stdio::null()).output().unwrap();
+    assert_refused(out, "memory_lock");
+}
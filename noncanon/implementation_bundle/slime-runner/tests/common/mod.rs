@@ -0,0 +1,101 @@
+// Shared by the integration tests that spawn the runner binary.
+//
+// The runner refuses to boot as root and requires `mlockall` (see
+// `src/selfcheck.rs`). `runner()` launches it the way a unit would:
+// unprivileged, with an unlimited memlock limit. Tests that call it are
+// `#[ignore]`d by default: they need a host where that limit can be raised
+// (`LimitMEMLOCK=infinity`, or root), and fail rather than pass without it.
+//
+// The M-B tests run the release CoreSpec artifacts instead; `corespec()`
+// builds them.
+
+#![allow(dead_code)]
+
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+pub const BIN: &str = env!("CARGO_BIN_EXE_slime-runner");
+pub const NOBODY: u32 = 65534;
+pub const EGRESS: &str = "/run/slime/egress.sock";
+
+pub fn is_root() -> bool {
+    // SAFETY: plain id query.
+    unsafe { libc::geteuid() == 0 }
+}
+
+/// True if `nobody` can reach and execute the runner binary.
+pub fn executable_by_nobody() -> bool {
+    let path = Path::new(BIN);
+    path.ancestors().all(|p| {
+        p.as_os_str().is_empty()
+            || std::fs::metadata(p).is_ok_and(|m| m.permissions().mode() & 0o001 != 0)
+    })
+}
+
+/// The runner with a cleared environment, set up to pass the boot
+/// self-check. Panics if this process cannot set it up.
+pub fn runner() -> Command {
+    let unlimited = libc::rlimit {
+        rlim_cur: libc::RLIM_INFINITY,
+        rlim_max: libc::RLIM_INFINITY,
+    };
+    // SAFETY: `unlimited` outlives the call. Inherited by every child.
+    let raised = unsafe { libc::setrlimit(libc::RLIMIT_MEMLOCK, &unlimited) } == 0;
+    assert!(raised, "cannot raise RLIMIT_MEMLOCK: run with LimitMEMLOCK=infinity or as root");
+    let mut cmd = Command::new(BIN);
+    cmd.env_clear();
+    if is_root() {
+        assert!(executable_by_nobody(), "runner binary is not executable by nobody");
+        cmd.uid(NOBODY).gid(NOBODY);
+    }
+    cmd
+}
+
+/// Panics if a live egress socket would let the runner boot.
+pub fn assert_no_egress() {
+    assert!(!Path::new(EGRESS).exists(), "a live egress socket is present at {}", EGRESS);
+}
+
+/// Panics unless the canon paths and port 8080 are free for a runner that
+/// boots: no live egress socket, `/run/slime` and `/var/log/slime` in
+/// place, and the log handed to `nobody` when the runner will run as it.
+pub fn require_environment() {
+    assert_no_egress();
+    assert!(
+        std::fs::create_dir_all("/run/slime").is_ok() && std::fs::create_dir_all("/var/log/slime").is_ok(),
+        "cannot create /run/slime or /var/log/slime"
+    );
+    if is_root() {
+        let log = Path::new("/var/log/slime");
+        for path in [log, &log.join("audit.log")] {
+            assert!(
+                !path.exists() || std::os::unix::fs::chown(path, Some(NOBODY), None).is_ok(),
+                "cannot hand {} to nobody",
+                path.display()
+            );
+        }
+    }
+    assert!(std::net::TcpListener::bind("127.0.0.1:8080").is_ok(), "port 8080 in use");
+}
+
+/// The release CoreSpec binary for `profile` (`enterprise` or `agent`),
+/// built by `scripts/build_corespec.sh`. Panics with the build log if the
+/// build fails.
+pub fn corespec(profile: &str) -> PathBuf {
+    let out = Command::new("bash")
+        .arg("scripts/build_corespec.sh")
+        .arg(profile)
+        .output()
+        .unwrap();
+    assert!(
+        out.status.success(),
+        "scripts/build_corespec.sh {} failed:\n{}",
+        profile,
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let path = PathBuf::from(String::from_utf8(out.stdout).unwrap().trim());
+    assert!(path.exists(), "{} was not built", path.display());
+    path
+}
@@ -5,14 +5,20 @@
 //
 // V1_INVARIANTS ref: §2 (Zero Runtime Configuration), §4 (CoreSpec MUST NOT exist as runtime file)
 
+mod common;
+
+use std::path::PathBuf;
 use std::process::{Command, Stdio};
 
-fn bin_path_enterprise() -> String {
-    std::env::var("SLIME_BIN_ENTERPRISE")
-        .unwrap_or_else(|_| "target/release/slime-core-enterprise".into())
+fn bin_path_enterprise() -> PathBuf {
+    std::env::var_os("SLIME_BIN_ENTERPRISE")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| common::corespec("enterprise"))
 }
 
 #[test]
+// Kill only: reaping the child is not part of what M-B01 observes.
+#[allow(clippy::zombie_processes)]
 fn mb01_controlled_env_clear_spawn() {
     let mut cmd = Command::new(bin_path_enterprise());
     cmd.env_clear()
@@ -5,6 +5,8 @@
 //
 // V1_INVARIANTS ref: §1.1 (Distinct CoreSpec identity)
 
+mod common;
+
 use blake3::Hasher;
 use std::{fs, path::Path};
 
@@ -17,17 +19,8 @@ fn hash_file(p: &Path) -> String {
 
 #[test]
 fn mb02_binary_diff_enterprise_vs_agent() {
-    let ent = Path::new("target/release/slime-core-enterprise");
-    let agt = Path::new("target/release/slime-core-agent");
-
-    assert!(
-        ent.exists(),
-        "M-B02: enterprise binary missing — build it first (scripts/build_corespec.sh enterprise)"
-    );
-    assert!(
-        agt.exists(),
-        "M-B02: agent binary missing — build it first (scripts/build_corespec.sh agent)"
-    );
+    let ent = &common::corespec("enterprise");
+    let agt = &common::corespec("agent");
 
     let h_enterprise = hash_file(ent);
     let h_agent = hash_file(agt);
@@ -21,6 +21,16 @@ fn run(cmd: &mut Command) -> (i32, String) {
     (code, format!("{}\n{}", stdout, stderr))
 }
 
+/// The artifact path `build_corespec.sh` prints last.
+fn built_path(output: &str) -> String {
+    output
+        .lines()
+        .rfind(|l| l.contains("slime-core-enterprise"))
+        .unwrap_or("")
+        .trim()
+        .to_string()
+}
+
 fn extract_hash(output: &str) -> String {
     output
         .lines()
@@ -31,6 +41,7 @@ fn extract_hash(output: &str) -> String {
 }
 
 #[test]
+#[ignore = "runs `cargo clean` on the workspace and three release builds; run it alone"]
 fn mb03_build_repro_three_times_same_hash_enterprise() {
     // Log toolchain for CI traceability
     if let Ok(out) = Command::new("rustc").arg("--version").output() {
@@ -57,7 +68,7 @@ fn mb03_build_repro_three_times_same_hash_enterprise() {
     let (c_h1, h1o) = run(
         Command::new("bash")
             .arg("scripts/hash_artifact.sh")
-            .arg("target/release/slime-core-enterprise"),
+            .arg(built_path(&o1)),
     );
     assert_eq!(c_h1, 0, "M-B03: hash 1 failed:\n{}", h1o);
     let h1 = extract_hash(&h1o);
@@ -75,7 +86,7 @@ fn mb03_build_repro_three_times_same_hash_enterprise() {
     let (c_h2, h2o) = run(
         Command::new("bash")
             .arg("scripts/hash_artifact.sh")
-            .arg("target/release/slime-core-enterprise"),
+            .arg(built_path(&o2)),
     );
     assert_eq!(c_h2, 0, "M-B03: hash 2 failed:\n{}", h2o);
     let h2 = extract_hash(&h2o);
@@ -93,7 +104,7 @@ fn mb03_build_repro_three_times_same_hash_enterprise() {
     let (c_h3, h3o) = run(
         Command::new("bash")
             .arg("scripts/hash_artifact.sh")
-            .arg("target/release/slime-core-enterprise"),
+            .arg(built_path(&o3)),
     );
     assert_eq!(c_h3, 0, "M-B03: hash 3 failed:\n{}", h3o);
     let h3 = extract_hash(&h3o);
@@ -12,6 +12,8 @@
 //
 // V1_INVARIANTS ref: §2 (MUST NOT mmap/parse policy files), §4 (CoreSpec MUST NOT exist as runtime file)
 
+mod common;
+
 use std::{fs, process::Command};
 
 // Patterns that must NOT appear in file-related syscall lines.
@@ -42,27 +44,42 @@ const SYSTEM_ALLOWLIST: &[&str] = &[
 ];
 
 #[test]
+#[ignore = "needs strace"]
 fn mb04_no_profile_open_strace_boot() {
+    let bin = common::corespec("enterprise");
+    // One log prefix per run; nothing left over from an earlier run is read.
+    let prefix = format!("slime_strace_boot-{}.log", std::process::id());
+    let clear = || {
+        for entry in fs::read_dir("/tmp").expect("M-B04: cannot read /tmp").flatten() {
+            if entry.file_name().to_string_lossy().starts_with(&prefix) {
+                let _ = fs::remove_file(entry.path());
+            }
+        }
+    };
+    clear();
+
     let out = Command::new("bash")
         .arg("scripts/run_strace_boot.sh")
-        .arg("target/release/slime-core-enterprise")
-        .arg("/tmp/slime_strace_boot.log")
+        .arg(&bin)
+        .arg(format!("/tmp/{}", prefix))
         .output()
         .expect("M-B04: failed to run strace script");
+    assert!(
+        out.status.success(),
+        "M-B04: strace script failed:\n{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
 
-    // strace may write multiple files with -ff: /tmp/slime_strace_boot.log.<pid>
+    // strace writes one file per process with -ff: /tmp/<prefix>.<pid>
     let mut logs: Vec<String> = vec![];
-    for entry in fs::read_dir("/tmp").expect("M-B04: cannot read /tmp") {
-        let entry = entry.expect("dir entry");
-        let p = entry.path();
-        if let Some(name) = p.file_name().and_then(|s| s.to_str()) {
-            if name.starts_with("slime_strace_boot.log") {
-                if let Ok(content) = fs::read_to_string(&p) {
-                    logs.push(content);
-                }
+    for entry in fs::read_dir("/tmp").expect("M-B04: cannot read /tmp").flatten() {
+        if entry.file_name().to_string_lossy().starts_with(&prefix) {
+            if let Ok(content) = fs::read_to_string(entry.path()) {
+                logs.push(content);
             }
         }
     }
+    clear();
     assert!(!logs.is_empty(), "M-B04: no strace logs found — check strace availability");
 
     let hay = logs.join("\n");
@@ -10,6 +10,8 @@
 // V1_INVARIANTS ref: §7.2 (Fail-Closed — ingress MUST NOT open unless
 //                          all initialization completes successfully)
 
+mod common;
+
 use std::net::{SocketAddr, TcpStream};
 use std::process::{Command, Stdio};
 use std::time::{Duration, Instant};
@@ -27,10 +29,11 @@ fn try_connect(addr: SocketAddr, timeout: Duration) -> bool {
 
 #[test]
 fn mb05_ingress_must_not_bind_when_egress_missing() {
+    let bin = common::corespec("enterprise");
     // Ensure egress socket is absent before spawning
     let _ = std::fs::remove_file("/run/slime/egress.sock");
 
-    let mut child = Command::new("target/release/slime-core-enterprise")
+    let mut child = Command::new(bin)
         .env_clear()
         .stdin(Stdio::null())
         .stdout(Stdio::null())
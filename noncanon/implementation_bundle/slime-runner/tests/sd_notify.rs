@@ -0,0 +1,112 @@
+// systemd readiness — READY=1 only after a complete boot
+//
+// Proves: with egress absent the runner never sends READY=1 to
+// NOTIFY_SOCKET; it sends STOPPING=1 and exits 1. On a boot that succeeds,
+// READY=1 is the first datagram and arrives only once egress is connected
+// and ingress is bound; SIGTERM then brings STOPPING=1. WATCHDOG=1 is
+// covered by unit tests in `src/notify.rs`.
+//
+// Ignored by default: needs LimitMEMLOCK=infinity (see `common/mod.rs`).
+// The successful boot also needs the fixed canon paths (`/run/slime`,
+// `/var/log/slime/`) and port 8080, so builds that serve ingress on the
+// local socket or over TLS do not compile it.
+//
+// V1_INVARIANTS ref: §7.2 (Fail-Closed — no partial initialization)
+
+mod common;
+
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::net::UnixDatagram;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::Duration;
+
+/// A datagram socket at a fresh path any user may send to.
+fn notify_socket(read_timeout: Duration) -> (PathBuf, UnixDatagram) {
+    let path = std::env::temp_dir().join(format!("slime-sd-notify-{}", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let sock = UnixDatagram::bind(&path).unwrap();
+    // The runner may be running as `nobody`.
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o666)).unwrap();
+    sock.set_read_timeout(Some(read_timeout)).unwrap();
+    (path, sock)
+}
+
+/// Every datagram until the read timeout.
+fn drain(sock: &UnixDatagram) -> Vec<String> {
+    let mut received = Vec::new();
+    let mut buf = [0u8; 64];
+    while let Ok(n) = sock.recv(&mut buf) {
+        received.push(String::from_utf8(buf[..n].to_vec()).unwrap());
+    }
+    received
+}
+
+#[test]
+#[ignore = "needs LimitMEMLOCK=infinity"]
+fn failing_boot_never_reports_ready() {
+    common::assert_no_egress();
+    let mut runner = common::runner();
+    let (path, sock) = notify_socket(Duration::from_millis(500));
+
+    let status = runner
+        .env("NOTIFY_SOCKET", &path)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .unwrap();
+    assert_eq!(status.code(), Some(1));
+
+    let received = drain(&sock);
+    let _ = std::fs::remove_file(&path);
+
+    assert_eq!(received, ["STOPPING=1"]);
+}
+
+#[cfg(not(any(feature = "corespec_ingress_unix", feature = "corespec_ingress_tls")))]
+#[test]
+#[ignore = "needs LimitMEMLOCK=infinity, /run/slime, /var/log/slime and port 8080"]
+fn successful_boot_reports_ready_after_egress_and_ingress() {
+    use std::net::TcpStream;
+    use std::os::unix::net::UnixListener;
+    use std::process::Command;
+
+    let mut runner = common::runner();
+    common::require_environment();
+    // Fake actuator, left unaccepted: the runner's connection waits in
+    // its backlog until READY=1 is in.
+    let actuator = UnixListener::bind(common::EGRESS).unwrap();
+    std::fs::set_permissions(common::EGRESS, std::fs::Permissions::from_mode(0o666)).unwrap();
+    let (path, sock) = notify_socket(Duration::from_secs(5));
+
+    let mut child = runner
+        .env("NOTIFY_SOCKET", &path)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+
+    let mut buf = [0u8; 64];
+    let first = sock.recv(&mut buf).map(|n| String::from_utf8_lossy(&buf[..n]).into_owned());
+    // Both ends must already be up when READY=1 is in.
+    actuator.set_nonblocking(true).unwrap();
+    let egress = actuator.accept();
+    let ingress = TcpStream::connect("127.0.0.1:8080").is_ok();
+
+    let killed = Command::new("kill")
+        .args(["-TERM", &child.id().to_string()])
+        .status()
+        .unwrap();
+    assert!(killed.success());
+    let status = child.wait().unwrap();
+    sock.set_read_timeout(Some(Duration::from_millis(500))).unwrap();
+    let rest = drain(&sock);
+    let _ = std::fs::remove_file(&path);
+    let _ = std::fs::remove_file(common::EGRESS);
+
+    assert_eq!(first.ok().as_deref(), Some("READY=1"));
+    assert!(egress.is_ok(), "READY=1 before egress was connected");
+    assert!(ingress, "READY=1 before ingress was bound");
+    assert_eq!(status.code(), Some(0));
+    assert_eq!(rest, ["STOPPING=1"]);
+}
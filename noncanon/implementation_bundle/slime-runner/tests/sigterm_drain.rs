@@ -0,0 +1,118 @@
+// Graceful shutdown — SIGTERM under load drains in-flight effects
+//
+// Proves: after SIGTERM the runner stops accepting, finishes the request in
+// flight and exits 0; every AUTHORIZED reply a client received has exactly
+// one 32-byte frame on egress, and no frame exists without its reply.
+//
+// Ignored by default: needs LimitMEMLOCK=infinity (see `common/mod.rs`), the
+// fixed canon paths (`/run/slime/egress.sock`, `/var/log/slime/`) and port
+// 8080, and fails when they are unavailable or already in use. Builds that
+// serve ingress on the local socket or over TLS do not compile it. When the
+// test runs as root the runner runs as `nobody`, which then owns
+// `/var/log/slime/` as `slime` would in a deployment.
+//
+// V1_INVARIANTS ref: §7.2 (Fail-Closed)
+// EGRESS_SOCKET_SPEC ref: "Authorization produces a write."
+
+#![cfg(not(any(feature = "corespec_ingress_unix", feature = "corespec_ingress_tls")))]
+
+mod common;
+
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::net::UnixListener;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const CLIENTS: usize = 4;
+const REQUEST: &[u8] =
+    b"POST / HTTP/1.1\r\nContent-Length: 31\r\n\r\n{\"domain\":\"test\",\"magnitude\":1}";
+
+/// One request; true if the reply was AUTHORIZED, None if not served.
+fn request() -> Option<bool> {
+    let mut s = TcpStream::connect("127.0.0.1:8080").ok()?;
+    s.write_all(REQUEST).ok()?;
+    let _ = s.shutdown(std::net::Shutdown::Write);
+    let mut resp = Vec::new();
+    s.read_to_end(&mut resp).ok()?;
+    if resp.is_empty() {
+        return None;
+    }
+    Some(resp.ends_with(b"{\"status\":\"AUTHORIZED\"}"))
+}
+
+#[test]
+#[ignore = "needs LimitMEMLOCK=infinity, /run/slime, /var/log/slime and port 8080"]
+fn sigterm_under_load_keeps_replies_and_frames_paired() {
+    let mut runner = common::runner();
+    common::require_environment();
+
+    // Fake actuator: count bytes until the runner closes egress.
+    let actuator = UnixListener::bind(common::EGRESS).unwrap();
+    std::fs::set_permissions(common::EGRESS, std::fs::Permissions::from_mode(0o666)).unwrap();
+    let frames = std::thread::spawn(move || {
+        let (mut s, _) = actuator.accept().unwrap();
+        let mut buf = Vec::new();
+        let _ = s.read_to_end(&mut buf);
+        buf
+    });
+
+    let mut child = runner
+        .stdin(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while TcpStream::connect("127.0.0.1:8080").is_err() {
+        assert!(Instant::now() < deadline, "runner never bound ingress");
+        std::thread::sleep(Duration::from_millis(10));
+    }
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let authorized = Arc::new(AtomicU64::new(0));
+    let clients: Vec<_> = (0..CLIENTS)
+        .map(|_| {
+            let (stop, authorized) = (stop.clone(), authorized.clone());
+            std::thread::spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    match request() {
+                        Some(true) => {
+                            authorized.fetch_add(1, Ordering::Relaxed);
+                        }
+                        Some(false) => {}
+                        None => std::thread::sleep(Duration::from_millis(1)),
+                    }
+                }
+            })
+        })
+        .collect();
+
+    std::thread::sleep(Duration::from_millis(300));
+    let killed = Command::new("kill")
+        .args(["-TERM", &child.id().to_string()])
+        .status()
+        .unwrap();
+    assert!(killed.success());
+
+    let status = child.wait().unwrap();
+    stop.store(true, Ordering::Relaxed);
+    for c in clients {
+        c.join().unwrap();
+    }
+    let written = frames.join().unwrap();
+    let _ = std::fs::remove_file(common::EGRESS);
+
+    let mut stderr = String::new();
+    let _ = child.stderr.take().unwrap().read_to_string(&mut stderr);
+    assert_eq!(status.code(), Some(0), "{}", stderr);
+    assert!(stderr.contains("\"event\":\"shutdown_requested\""), "{}", stderr);
+
+    let authorized = authorized.load(Ordering::Relaxed);
+    assert!(authorized > 0, "no load reached the runner");
+    assert_eq!(written.len() % 32, 0);
+    assert_eq!(written.len() as u64 / 32, authorized);
+}
@@ -0,0 +1,57 @@
+//! Offline verifier for the runner's hash-chained audit log.
+//!
+//! Usage:
+//!   slime-audit verify <audit.log> [--anchor <seq>:<hash>]
+//!
+//! `--anchor` takes the `anchor` of an `audit_checkpoint` event from the
+//! runner's stderr log (journal): the log must still hold that line
+//! unchanged, so a tail cut back past it is reported.
+//!
+//! Exit codes: 0 = chain intact, 1 = chain broken (first broken link on
+//! stdout), 2 = usage or read error.
+
+use slime_audit_chain::{to_hex, verify, verify_anchored, Anchor};
+use std::process;
+
+fn usage(program: &str) -> ! {
+    eprintln!("Usage: {} verify <audit.log> [--anchor <seq>:<hash>]", program);
+    process::exit(2);
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let anchor = match args.len() {
+        3 => None,
+        5 if args[3] == "--anchor" => {
+            Some(Anchor::parse(&args[4]).unwrap_or_else(|| usage(&args[0])))
+        }
+        _ => usage(&args[0]),
+    };
+    if args[1] != "verify" {
+        usage(&args[0]);
+    }
+
+    let log = std::fs::read(&args[2]).unwrap_or_else(|e| {
+        eprintln!("cannot read {}: {}", args[2], e);
+        process::exit(2);
+    });
+
+    let result = match &anchor {
+        Some(anchor) => verify_anchored(&log, anchor),
+        None => verify(&log),
+    };
+    match result {
+        Ok(summary) => {
+            println!(
+                "OK records={} checkpoints={} head={}",
+                summary.records,
+                summary.checkpoints,
+                String::from_utf8_lossy(&to_hex(&summary.head))
+            );
+        }
+        Err((line, reason)) => {
+            println!("BROKEN line={} reason={}", line, reason.as_str());
+            process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,110 @@
+// Audit hash chain — offline verification
+//
+// Proves: `slime-audit verify` accepts an intact chain and reports the first
+// broken link for a truncated tail, a deleted line and an edited line, and,
+// given a checkpoint anchor, whole trailing lines cut off.
+//
+// FULL_STACK_CONFORMANCE ref: R-1 (reasons audit-only; the audit trail itself
+// must be tamper-evident)
+
+use slime_audit_chain::{hash, Anchor, Chain, MAX_LINE_BYTES};
+use std::process::Command;
+
+fn build_log(n: usize) -> Vec<Vec<u8>> {
+    let mut chain = Chain::genesis();
+    let mut buf = [0u8; MAX_LINE_BYTES];
+    (0..n)
+        .map(|i| {
+            let fields = if chain.checkpoint_due() {
+                "\"kind\":\"checkpoint\",\"ts_ms\":0".to_string()
+            } else {
+                format!("\"kind\":\"decision\",\"ts_ms\":{},\"reason\":\"authorized\"", i)
+            };
+            let len = chain.seal(fields.as_bytes(), &mut buf).unwrap();
+            buf[..len].to_vec()
+        })
+        .collect()
+}
+
+fn run_verify(name: &str, log: &[u8]) -> (i32, String) {
+    run_verify_with(name, log, &[])
+}
+
+fn run_verify_with(name: &str, log: &[u8], extra: &[&str]) -> (i32, String) {
+    let path = std::env::temp_dir().join(format!("slime-audit-{}-{}.log", name, std::process::id()));
+    std::fs::write(&path, log).unwrap();
+    let out = Command::new(env!("CARGO_BIN_EXE_slime-audit"))
+        .arg("verify")
+        .arg(&path)
+        .args(extra)
+        .output()
+        .unwrap();
+    let _ = std::fs::remove_file(&path);
+    (out.status.code().unwrap(), String::from_utf8(out.stdout).unwrap())
+}
+
+#[test]
+fn intact_chain_verifies() {
+    let log = build_log(130).concat();
+    let (code, out) = run_verify("intact", &log);
+    assert_eq!(code, 0);
+    assert!(out.starts_with("OK records=130 checkpoints=2 head="), "{}", out);
+}
+
+#[test]
+fn truncated_tail_is_reported() {
+    let mut log = build_log(10).concat();
+    log.truncate(log.len() - 5);
+    let (code, out) = run_verify("truncated", &log);
+    assert_eq!(code, 1);
+    assert_eq!(out.trim_end(), "BROKEN line=10 reason=truncated record");
+}
+
+#[test]
+fn deleted_line_is_reported() {
+    let mut lines = build_log(10);
+    lines.remove(4);
+    let (code, out) = run_verify("deleted", &lines.concat());
+    assert_eq!(code, 1);
+    assert_eq!(out.trim_end(), "BROKEN line=5 reason=sequence gap");
+}
+
+#[test]
+fn edited_line_breaks_the_next_link() {
+    let mut lines = build_log(10);
+    let edited = String::from_utf8(lines[3].clone())
+        .unwrap()
+        .replace("\"authorized\"", "\"law_impossible\"");
+    lines[3] = edited.into_bytes();
+    let (code, out) = run_verify("edited", &lines.concat());
+    assert_eq!(code, 1);
+    assert_eq!(out.trim_end(), "BROKEN line=5 reason=previous-hash mismatch");
+}
+
+#[test]
+fn dropped_trailing_lines_are_reported_against_the_anchor() {
+    let lines = build_log(140);
+    // The second checkpoint (seq 129), as the runner reports it on stderr.
+    let checkpoint = lines[129].strip_suffix(b"\n").unwrap();
+    let anchor = Anchor { seq: 129, hash: hash(checkpoint) }.to_string();
+
+    let (code, out) = run_verify_with("anchored", &lines.concat(), &["--anchor", &anchor]);
+    assert_eq!(code, 0, "{}", out);
+
+    let cut = lines[..100].concat();
+    let (code, out) = run_verify("cut", &cut);
+    assert_eq!(code, 0, "without an anchor a clean cut is undetectable");
+    assert!(out.starts_with("OK records=100 "), "{}", out);
+    let (code, out) = run_verify_with("cut-anchored", &cut, &["--anchor", &anchor]);
+    assert_eq!(code, 1);
+    assert_eq!(out.trim_end(), "BROKEN line=130 reason=anchor mismatch");
+}
+
+#[test]
+fn usage_error_exits_2() {
+    let out = Command::new(env!("CARGO_BIN_EXE_slime-audit")).output().unwrap();
+    assert_eq!(out.status.code(), Some(2));
+    let log = build_log(3).concat();
+    let (code, _) = run_verify_with("bad-anchor", &log, &["--anchor", "2:zz"]);
+    assert_eq!(code, 2);
+}